@@ -5,3 +5,6 @@ pub mod maze_generator;
 
 /// A module that models a maze and its constituent parts.
 pub mod maze;
+
+/// A module for finding paths through mazes.
+pub mod maze_solver;
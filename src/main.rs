@@ -1,42 +1,193 @@
 use maze_lib::maze::cell_edge::CellEdge;
 use maze_lib::maze::coordinates::Coordinates;
 use maze_lib::maze::direction::Direction;
+use maze_lib::maze::Maze;
 use maze_lib::maze_generator;
+use maze_lib::maze_generator::OpeningStrategy;
 
-fn main() {
-    static WALL_CHAR: &str = "▏";
-    static FLOOR_CHAR: &str = "_";
-    static PASSAGE_CHAR: &str = " ";
+static WALL_CHAR: &str = "▏";
+static FLOOR_CHAR: &str = "_";
+static PASSAGE_CHAR: &str = " ";
+static TUNNEL_WALL_CHAR: &str = "┆";
+static TUNNEL_FLOOR_CHAR: &str = "┄";
+static BRIDGE_NORTH_SOUTH_CHAR: &str = "║";
+static BRIDGE_EAST_WEST_CHAR: &str = "═";
+static STAIRS_UP_CHAR: &str = "↑";
+static STAIRS_DOWN_CHAR: &str = "↓";
+static STAIRS_UP_DOWN_CHAR: &str = "↕";
 
-    let maze = maze_generator::generate(20, 10);
+fn main() {
+    let mut maze = maze_generator::generate(20, 10);
+    maze_generator::place_openings(
+        &mut maze,
+        OpeningStrategy::OppositeSides,
+        &mut rand::thread_rng(),
+    );
     println!("{:?}x{:?}", maze.columns(), maze.rows());
+    print!("{}", render(&maze));
+}
 
-    println!(
-        "{}",
-        (" ".to_owned() + FLOOR_CHAR).repeat((maze.columns()) as usize)
-    );
+/// Returns the maze drawn as text, one slice per level from the lowest up. Multi-level mazes
+/// head each slice with its level.
+fn render(maze: &Maze) -> String {
+    let mut output = String::new();
+    for level in 0..maze.levels() as i32 {
+        if maze.levels() > 1 {
+            output += &format!("Level {level}\n");
+        }
+        output += &render_level(maze, level);
+    }
+    output
+}
 
-    for row in (0..maze.rows() as i32).rev() {
-        for column in (0..maze.columns() as i32).rev() {
-            if let Some(cell) = maze.cell(&Coordinates::new(column, row)) {
-                if let Some(edge) = cell.edge(&Direction::East) {
-                    match edge {
-                        CellEdge::Wall | CellEdge::Border => print!("{}", WALL_CHAR),
-                        _ => print!("{}", PASSAGE_CHAR),
-                    }
-                }
-                if let Some(edge) = cell.edge(&Direction::South) {
-                    match edge {
-                        CellEdge::Wall | CellEdge::Border => print!("{}", FLOOR_CHAR),
-                        _ => print!("{}", PASSAGE_CHAR),
-                    }
-                }
-                if let Some(edge) = cell.edge(&Direction::West) {
-                    if edge == CellEdge::Border {
-                        println!("{}", WALL_CHAR);
+/// Returns one level of the maze drawn as text, one line per row plus one for the northern
+/// border. Masked-out cells are left blank, and walled off by the borders of their neighbors.
+/// In a weave maze, each crossing is drawn as a bridge running the way of its passage, and
+/// the edges where the tunnel under it starts and ends are drawn dashed. In a multi-level maze,
+/// a cell with stairs to the level above or below has them drawn in place of its floor.
+fn render_level(maze: &Maze, level: i32) -> String {
+    // The edge of the cell at the given location, if there is a cell there.
+    let edge = |column: i32, row: i32, direction: Direction| {
+        let coordinates = Coordinates::with_level(column, row, level);
+        if maze.in_bounds(&coordinates) {
+            maze.cell(&coordinates)
+                .and_then(|cell| cell.edge(&direction))
+        } else {
+            None
+        }
+    };
+    let is_closed =
+        |edge: Option<CellEdge>| matches!(edge, Some(CellEdge::Wall | CellEdge::Border));
+    let is_tunnel = |edge: Option<CellEdge>| edge == Some(CellEdge::Tunnel);
+    let is_crossing = |column: i32, row: i32| {
+        let coordinates = Coordinates::with_level(column, row, level);
+        maze.in_bounds(&coordinates)
+            && maze
+                .cell(&coordinates)
+                .is_some_and(|cell| cell.is_crossing())
+    };
+    let columns = maze.columns() as i32;
+    let rows = maze.rows() as i32;
+
+    let mut output = String::new();
+    for column in 0..columns {
+        output += PASSAGE_CHAR;
+        output += if is_closed(edge(column, rows - 1, Direction::North)) {
+            FLOOR_CHAR
+        } else {
+            PASSAGE_CHAR
+        };
+    }
+    output += "\n";
+
+    for row in (0..rows).rev() {
+        for column in 0..=columns {
+            // Each cell is drawn as its western wall followed by its floor. Tunnel edges are
+            // drawn as dashed walls, which the tunnel passes under.
+            let west = edge(column, row, Direction::West);
+            let east_of_previous = edge(column - 1, row, Direction::East);
+            output += if is_closed(west) || is_closed(east_of_previous) {
+                WALL_CHAR
+            } else if is_tunnel(west) || is_tunnel(east_of_previous) {
+                TUNNEL_WALL_CHAR
+            } else {
+                PASSAGE_CHAR
+            };
+            if column < columns {
+                let south = edge(column, row, Direction::South);
+                let north_of_next = edge(column, row - 1, Direction::North);
+                let above = edge(column, row, Direction::Above) == Some(CellEdge::Passage);
+                let below = edge(column, row, Direction::Below) == Some(CellEdge::Passage);
+                output += if above && below {
+                    STAIRS_UP_DOWN_CHAR
+                } else if above {
+                    STAIRS_UP_CHAR
+                } else if below {
+                    STAIRS_DOWN_CHAR
+                } else if is_crossing(column, row) {
+                    // A crossing's floor is drawn as the bridge that carries its passage
+                    // straight over the tunnel.
+                    if edge(column, row, Direction::North) == Some(CellEdge::Passage) {
+                        BRIDGE_NORTH_SOUTH_CHAR
+                    } else {
+                        BRIDGE_EAST_WEST_CHAR
                     }
-                }
+                } else if is_closed(south) || is_closed(north_of_next) {
+                    FLOOR_CHAR
+                } else if is_tunnel(south) || is_tunnel(north_of_next) {
+                    TUNNEL_FLOOR_CHAR
+                } else {
+                    PASSAGE_CHAR
+                };
             }
         }
+        output += "\n";
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maze_lib::maze_generator::{MazeAlgorithm, Weave};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn renders_crossing_as_bridge() {
+        // The middle cell's passage runs north to south, over a tunnel from west to east.
+        let mut maze = Maze::new(3, 3);
+        let middle = Coordinates::new(1, 1);
+        maze.set_edge(&middle, &Direction::North, CellEdge::Passage);
+        maze.set_edge(&middle, &Direction::South, CellEdge::Passage);
+        maze.set_edge(&middle, &Direction::West, CellEdge::Tunnel);
+        maze.set_edge(&middle, &Direction::East, CellEdge::Tunnel);
+        let mut cell = maze.cell(&middle).unwrap();
+        cell.set_crossing(true);
+        maze.set_cell(cell);
+        maze.close_unassigned_edges();
+
+        let rendered = render(&maze);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[2], "▏_┆║┆_▏");
+    }
+
+    #[test]
+    fn renders_stairs_between_levels() {
+        let mut maze = Maze::with_levels(2, 1, 3);
+        maze.set_edge(
+            &Coordinates::with_level(0, 0, 0),
+            &Direction::Above,
+            CellEdge::Passage,
+        );
+        maze.set_edge(
+            &Coordinates::with_level(0, 0, 1),
+            &Direction::Above,
+            CellEdge::Passage,
+        );
+        maze.close_unassigned_edges();
+
+        let rendered = render(&maze);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "Level 0");
+        assert_eq!(lines[2], "▏↑▏_▏");
+        assert_eq!(lines[5], "▏↕▏_▏");
+        assert_eq!(lines[8], "▏↓▏_▏");
+    }
+
+    #[test]
+    fn renders_every_crossing_in_weave_maze() {
+        let mut maze = Maze::new(12, 12);
+        Weave::new(0.5).carve(&mut maze, &mut ChaCha8Rng::seed_from_u64(23));
+        let crossings = maze
+            .into_iter()
+            .flatten()
+            .filter(|cell| cell.is_crossing())
+            .count();
+        assert!(crossings > 0);
+        let rendered = render(&maze);
+        let bridges = rendered.matches(BRIDGE_NORTH_SOUTH_CHAR).count()
+            + rendered.matches(BRIDGE_EAST_WEST_CHAR).count();
+        assert_eq!(bridges, crossings);
     }
 }
@@ -10,18 +10,35 @@ pub struct Cell {
     east_edge: Option<CellEdge>,
     south_edge: Option<CellEdge>,
     west_edge: Option<CellEdge>,
+    above_edge: Option<CellEdge>,
+    below_edge: Option<CellEdge>,
+    crossing: bool,
 }
 
 /// Associated functions to create and use a Cell struct.
 impl Cell {
-    /// Creates a new Cell with the given coordinates. The new cell has no assigned edges.
+    /// Creates a new Cell with the given coordinates, for a flat maze. The new cell has no
+    /// assigned compass edges, and its above and below edges are borders.
     pub fn new(coordinates: Coordinates) -> Cell {
+        Cell {
+            above_edge: Some(CellEdge::Border),
+            below_edge: Some(CellEdge::Border),
+            ..Cell::new_multilevel(coordinates)
+        }
+    }
+
+    /// Creates a new Cell with the given coordinates, for a maze with more than one level. The
+    /// new cell has no assigned edges, including its above and below edges.
+    pub fn new_multilevel(coordinates: Coordinates) -> Cell {
         Cell {
             coordinates,
             north_edge: None,
             east_edge: None,
             south_edge: None,
             west_edge: None,
+            above_edge: None,
+            below_edge: None,
+            crossing: false,
         }
     }
 
@@ -45,14 +62,46 @@ impl Cell {
         if self.west_edge.is_none() {
             vec.push(Direction::West);
         }
+        if self.above_edge.is_none() {
+            vec.push(Direction::Above);
+        }
+        if self.below_edge.is_none() {
+            vec.push(Direction::Below);
+        }
         vec
     }
 
+    /// Returns true if the Cell is a dead end: exactly one of its edges is a passage or a
+    /// tunnel.
+    pub fn is_dead_end(&self) -> bool {
+        Direction::ALL_LEVELS
+            .iter()
+            .filter(|direction| {
+                matches!(
+                    self.edge(direction),
+                    Some(CellEdge::Passage | CellEdge::Tunnel)
+                )
+            })
+            .count()
+            == 1
+    }
+
     /// Returns the Cell's coordinates.
     pub fn coordinates(self) -> Coordinates {
         self.coordinates
     }
 
+    /// Returns true if the Cell is a crossing in a weave maze: its passages run over a tunnel,
+    /// so its own tunnel edges lead under it rather than out of it.
+    pub fn is_crossing(&self) -> bool {
+        self.crossing
+    }
+
+    /// Sets whether the Cell is a crossing.
+    pub fn set_crossing(&mut self, crossing: bool) {
+        self.crossing = crossing;
+    }
+
     /// Returns the value of the Cell's edge in the given direction.
     pub fn edge(&self, direction: &Direction) -> Option<CellEdge> {
         match direction {
@@ -60,6 +109,8 @@ impl Cell {
             Direction::East => self.east_edge,
             Direction::South => self.south_edge,
             Direction::West => self.west_edge,
+            Direction::Above => self.above_edge,
+            Direction::Below => self.below_edge,
         }
     }
 
@@ -70,14 +121,18 @@ impl Cell {
             Direction::East => self.east_edge = cell_edge,
             Direction::South => self.south_edge = cell_edge,
             Direction::West => self.west_edge = cell_edge,
+            Direction::Above => self.above_edge = cell_edge,
+            Direction::Below => self.below_edge = cell_edge,
         }
     }
 
-    /// Returns the direction of one of the Cell's unassigned edges.
-    pub fn random_unassigned_direction(&self) -> Direction {
+    /// Returns the direction of one of the Cell's unassigned edges, chosen with the given
+    /// random number generator.
+    pub fn random_unassigned_direction<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
         let unassigned_directions = self.unassigned_directions();
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..unassigned_directions.len());
+        // Sample a u32 rather than a usize, so the same rng produces the same choice
+        // on 32-bit (wasm) and 64-bit targets.
+        let index = rng.gen_range(0..unassigned_directions.len() as u32) as usize;
         unassigned_directions[index]
     }
 }
@@ -85,6 +140,8 @@ impl Cell {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn unassigned_directions_none_assigned() {
@@ -141,6 +198,46 @@ mod tests {
         assert_eq!(cell.unassigned_directions(), vec![])
     }
 
+    #[test]
+    fn unassigned_directions_multilevel() {
+        let mut cell = Cell::new_multilevel(Coordinates::with_level(0, 0, 1));
+        cell.set_edge(&Direction::East, Some(CellEdge::Wall));
+        assert_eq!(
+            cell.unassigned_directions(),
+            vec![
+                Direction::North,
+                Direction::South,
+                Direction::West,
+                Direction::Above,
+                Direction::Below,
+            ]
+        );
+        assert_eq!(
+            Cell::new(Coordinates::new(0, 0)).edge(&Direction::Above),
+            Some(CellEdge::Border)
+        );
+    }
+
+    #[test]
+    fn is_crossing() {
+        let mut cell = Cell::new(Coordinates::new(0, 0));
+        assert!(!cell.is_crossing());
+        cell.set_crossing(true);
+        assert!(cell.is_crossing());
+    }
+
+    #[test]
+    fn tunnel_counts_towards_dead_end() {
+        let mut cell = Cell::new(Coordinates::new(0, 0));
+        cell.set_edge(&Direction::North, Some(CellEdge::Wall));
+        cell.set_edge(&Direction::East, Some(CellEdge::Tunnel));
+        cell.set_edge(&Direction::South, Some(CellEdge::Wall));
+        cell.set_edge(&Direction::West, Some(CellEdge::Border));
+        assert!(cell.is_dead_end());
+        cell.set_edge(&Direction::North, Some(CellEdge::Passage));
+        assert!(!cell.is_dead_end());
+    }
+
     #[test]
     fn is_fully_assigned_directions_none_assigned() {
         let cell = Cell::new(Coordinates::new(0, 0));
@@ -181,6 +278,26 @@ mod tests {
         assert!(cell.is_fully_assigned())
     }
 
+    #[test]
+    fn is_dead_end_with_one_passage() {
+        let mut cell = Cell::new(Coordinates::new(0, 0));
+        cell.set_edge(&Direction::North, Some(CellEdge::Border));
+        cell.set_edge(&Direction::East, Some(CellEdge::Wall));
+        cell.set_edge(&Direction::South, Some(CellEdge::Passage));
+        cell.set_edge(&Direction::West, Some(CellEdge::Wall));
+        assert!(cell.is_dead_end())
+    }
+
+    #[test]
+    fn is_not_dead_end_with_two_passages() {
+        let mut cell = Cell::new(Coordinates::new(0, 0));
+        cell.set_edge(&Direction::North, Some(CellEdge::Passage));
+        cell.set_edge(&Direction::East, Some(CellEdge::Wall));
+        cell.set_edge(&Direction::South, Some(CellEdge::Passage));
+        cell.set_edge(&Direction::West, Some(CellEdge::Wall));
+        assert!(!cell.is_dead_end())
+    }
+
     #[test]
     fn get_and_set_north() {
         let mut cell = Cell::new(Coordinates::new(0, 0));
@@ -216,4 +333,29 @@ mod tests {
         cell.set_edge(&direction, edge);
         assert_eq!(cell.edge(&direction), edge);
     }
+
+    #[test]
+    fn random_unassigned_direction_is_unassigned() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut cell = Cell::new(Coordinates::new(0, 0));
+        cell.set_edge(&Direction::North, Some(CellEdge::Border));
+        cell.set_edge(&Direction::West, Some(CellEdge::Wall));
+        for _ in 0..20 {
+            let direction = cell.random_unassigned_direction(&mut rng);
+            assert!(cell.unassigned_directions().contains(&direction));
+        }
+    }
+
+    #[test]
+    fn random_unassigned_direction_is_reproducible() {
+        let cell = Cell::new(Coordinates::new(0, 0));
+        let mut rng1 = ChaCha8Rng::seed_from_u64(5);
+        let mut rng2 = ChaCha8Rng::seed_from_u64(5);
+        for _ in 0..20 {
+            assert_eq!(
+                cell.random_unassigned_direction(&mut rng1),
+                cell.random_unassigned_direction(&mut rng2)
+            );
+        }
+    }
 }
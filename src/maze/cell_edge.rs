@@ -7,4 +7,10 @@ pub enum CellEdge {
     Wall,
     /// The edge is a passage to another cell.
     Passage,
+    /// The edge is a gap in the border of the maze: an entrance or an exit.
+    Opening,
+    /// The edge is part of a passage that tunnels under a crossing cell, from the cell on
+    /// one side of the crossing to the cell on the other. Both the crossing and the cells it
+    /// joins have tunnel edges facing each other.
+    Tunnel,
 }
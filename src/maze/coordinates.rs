@@ -1,28 +1,66 @@
-/// Represents a location in a maze as column and row.
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// Represents a location in a maze as column, row and level. Flat mazes only use level 0.
+#[derive(Copy, Clone, PartialEq)]
 pub struct Coordinates {
     column: i32,
     row: i32,
+    level: i32,
 }
 
 /// Associated functions to create and use a Coordinates struct.
 impl Coordinates {
     /// Coordinates struct that can be added to another coordinate to get the
-    /// coordinates above it.
-    pub const UP: Coordinates = Coordinates { column: 0, row: 1 };
+    /// coordinates north of it, which are above it on the page. See [Coordinates::ABOVE]
+    /// for the level above.
+    pub const UP: Coordinates = Coordinates {
+        column: 0,
+        row: 1,
+        level: 0,
+    };
     /// Coordinates struct that can be added to another coordinate to get the
     /// coordinates to the right of it.
-    pub const RIGHT: Coordinates = Coordinates { column: 1, row: 0 };
+    pub const RIGHT: Coordinates = Coordinates {
+        column: 1,
+        row: 0,
+        level: 0,
+    };
     /// Coordinates struct that can be added to another coordinate to get the
-    /// coordinates below it.
-    pub const DOWN: Coordinates = Coordinates { column: 0, row: -1 };
+    /// coordinates south of it, which are below it on the page. See [Coordinates::BELOW]
+    /// for the level below.
+    pub const DOWN: Coordinates = Coordinates {
+        column: 0,
+        row: -1,
+        level: 0,
+    };
     /// Coordinates struct that can be added to another coordinate to get the
     /// coordinates to the left of it.
-    pub const LEFT: Coordinates = Coordinates { column: -1, row: 0 };
-
-    /// Creates a new Coordinates struct with the given column and row.
+    pub const LEFT: Coordinates = Coordinates {
+        column: -1,
+        row: 0,
+        level: 0,
+    };
+    /// Coordinates struct that can be added to another coordinate to get the
+    /// coordinates on the level above it.
+    pub const ABOVE: Coordinates = Coordinates {
+        column: 0,
+        row: 0,
+        level: 1,
+    };
+    /// Coordinates struct that can be added to another coordinate to get the
+    /// coordinates on the level below it.
+    pub const BELOW: Coordinates = Coordinates {
+        column: 0,
+        row: 0,
+        level: -1,
+    };
+
+    /// Creates a new Coordinates struct with the given column and row, on level 0.
     pub fn new(column: i32, row: i32) -> Coordinates {
-        Coordinates { column, row }
+        Coordinates::with_level(column, row, 0)
+    }
+
+    /// Creates a new Coordinates struct with the given column, row and level.
+    pub fn with_level(column: i32, row: i32, level: i32) -> Coordinates {
+        Coordinates { column, row, level }
     }
 
     /// Returns the Coordinates struct's row.
@@ -34,6 +72,23 @@ impl Coordinates {
     pub fn column(&self) -> i32 {
         self.column
     }
+
+    /// Returns the Coordinates struct's level.
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+}
+
+/// Leaves out the level when it is 0, so that coordinates in flat mazes read as before.
+impl std::fmt::Debug for Coordinates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Coordinates");
+        debug.field("column", &self.column).field("row", &self.row);
+        if self.level != 0 {
+            debug.field("level", &self.level);
+        }
+        debug.finish()
+    }
 }
 
 impl std::ops::AddAssign<Coordinates> for Coordinates {
@@ -49,6 +104,7 @@ impl std::ops::Add<Coordinates> for Coordinates {
         Coordinates {
             column: self.column + increment.column,
             row: self.row + increment.row,
+            level: self.level + increment.level,
         }
     }
 }
@@ -129,4 +185,19 @@ mod tests {
             "Coordinates { column: 4, row: 2 }"
         )
     }
+
+    #[test]
+    fn handles_format_with_level() {
+        let coordinates = Coordinates::with_level(4, 2, 1);
+        assert_eq!(
+            format!("{coordinates:?}"),
+            "Coordinates { column: 4, row: 2, level: 1 }"
+        )
+    }
+
+    #[test]
+    fn adding_above_moves_up_a_level() {
+        let coordinates = Coordinates::with_level(4, 2, 1) + Coordinates::ABOVE;
+        assert_eq!(coordinates, Coordinates::with_level(4, 2, 2));
+    }
 }
@@ -7,10 +7,36 @@ pub enum Direction {
     East,
     South,
     West,
+    /// Up to the level above, by stairs or a ladder. Unlike [Coordinates::UP], which is
+    /// north, this leaves the level.
+    Above,
+    /// Down to the level below. Unlike [Coordinates::DOWN], which is south, this leaves the
+    /// level.
+    Below,
 }
 
 /// Associated functions to create and use a Direction enum.
 impl Direction {
+    /// The four compass Directions, in clockwise order starting from North, which are all
+    /// of the Directions in a flat maze.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// All of the Directions in a maze with more than one level: the compass Directions,
+    /// followed by Above and Below.
+    pub const ALL_LEVELS: [Direction; 6] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+        Direction::Above,
+        Direction::Below,
+    ];
+
     /// Returns the Direction's opposite.
     pub fn opposite(&self) -> Direction {
         match self {
@@ -18,6 +44,8 @@ impl Direction {
             Direction::East => Direction::West,
             Direction::South => Direction::North,
             Direction::West => Direction::East,
+            Direction::Above => Direction::Below,
+            Direction::Below => Direction::Above,
         }
     }
 
@@ -29,6 +57,8 @@ impl Direction {
             Direction::East => Coordinates::RIGHT,
             Direction::South => Coordinates::DOWN,
             Direction::West => Coordinates::LEFT,
+            Direction::Above => Coordinates::ABOVE,
+            Direction::Below => Coordinates::BELOW,
         }
     }
 }
@@ -56,4 +86,14 @@ mod tests {
     fn opposite_of_west() {
         assert_eq!(Direction::West.opposite(), Direction::East)
     }
+
+    #[test]
+    fn opposite_of_above() {
+        assert_eq!(Direction::Above.opposite(), Direction::Below)
+    }
+
+    #[test]
+    fn opposite_of_below() {
+        assert_eq!(Direction::Below.opposite(), Direction::Above)
+    }
 }
@@ -0,0 +1,102 @@
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::hex::coordinates::HexCoordinates;
+use crate::maze::hex::direction::HexDirection;
+
+/// Represents a cell in a hex maze, with coordinates and six edges.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HexCell {
+    coordinates: HexCoordinates,
+    edges: [Option<CellEdge>; 6],
+}
+
+/// Associated functions to create and use a HexCell struct.
+impl HexCell {
+    /// Creates a new HexCell with the given coordinates and no assigned edges.
+    pub fn new(coordinates: HexCoordinates) -> HexCell {
+        HexCell {
+            coordinates,
+            edges: [None; 6],
+        }
+    }
+
+    /// Returns the HexCell's coordinates.
+    pub fn coordinates(self) -> HexCoordinates {
+        self.coordinates
+    }
+
+    /// Returns true if all of the HexCell's edges have been assigned.
+    pub fn is_fully_assigned(&self) -> bool {
+        self.edges.iter().all(Option::is_some)
+    }
+
+    /// Returns a vector containing the HexCell's unassigned edges, in clockwise order
+    /// starting from NorthEast.
+    pub fn unassigned_directions(&self) -> Vec<HexDirection> {
+        HexDirection::ALL
+            .into_iter()
+            .filter(|direction| self.edge(direction).is_none())
+            .collect()
+    }
+
+    /// Returns true if the HexCell is a dead end: exactly one of its edges is a passage.
+    pub fn is_dead_end(&self) -> bool {
+        self.edges
+            .iter()
+            .filter(|edge| **edge == Some(CellEdge::Passage))
+            .count()
+            == 1
+    }
+
+    /// Returns the value of the HexCell's edge in the given direction.
+    pub fn edge(&self, direction: &HexDirection) -> Option<CellEdge> {
+        self.edges[*direction as usize]
+    }
+
+    /// Sets the HexCell's edge in the given direction to the given value.
+    pub fn set_edge(&mut self, direction: &HexDirection, cell_edge: Option<CellEdge>) {
+        self.edges[*direction as usize] = cell_edge;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unassigned_directions_none_assigned() {
+        let cell = HexCell::new(HexCoordinates::new(0, 0));
+        assert_eq!(cell.unassigned_directions(), HexDirection::ALL.to_vec());
+        assert!(!cell.is_fully_assigned());
+    }
+
+    #[test]
+    fn unassigned_directions_some_assigned() {
+        let mut cell = HexCell::new(HexCoordinates::new(0, 0));
+        cell.set_edge(&HexDirection::East, Some(CellEdge::Wall));
+        cell.set_edge(&HexDirection::SouthWest, Some(CellEdge::Passage));
+        assert_eq!(
+            cell.unassigned_directions(),
+            vec![
+                HexDirection::NorthEast,
+                HexDirection::SouthEast,
+                HexDirection::West,
+                HexDirection::NorthWest,
+            ]
+        );
+        assert_eq!(cell.edge(&HexDirection::East), Some(CellEdge::Wall));
+    }
+
+    #[test]
+    fn dead_end_has_one_passage() {
+        let mut cell = HexCell::new(HexCoordinates::new(0, 0));
+        for direction in HexDirection::ALL {
+            cell.set_edge(&direction, Some(CellEdge::Wall));
+        }
+        assert!(cell.is_fully_assigned());
+        assert!(!cell.is_dead_end());
+        cell.set_edge(&HexDirection::NorthWest, Some(CellEdge::Passage));
+        assert!(cell.is_dead_end());
+        cell.set_edge(&HexDirection::East, Some(CellEdge::Passage));
+        assert!(!cell.is_dead_end());
+    }
+}
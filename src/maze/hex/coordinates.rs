@@ -0,0 +1,79 @@
+/// Represents a location in a hex maze in axial coordinates: `q` counts cells to the east
+/// along a row, and `r` counts rows to the north. Moving north-east keeps `q` the same, so
+/// every [HexDirection](crate::maze::hex::direction::HexDirection) is a fixed step.
+///
+/// A rectangular hex maze stores its rows in offset columns instead, with every odd row
+/// shifted half a cell east. See [HexCoordinates::from_offset].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HexCoordinates {
+    q: i32,
+    r: i32,
+}
+
+/// Associated functions to create and use a HexCoordinates struct.
+impl HexCoordinates {
+    /// Creates a new HexCoordinates struct with the given axial coordinates.
+    pub fn new(q: i32, r: i32) -> HexCoordinates {
+        HexCoordinates { q, r }
+    }
+
+    /// Creates a new HexCoordinates struct for the cell in the given offset column and row.
+    pub fn from_offset(column: i32, row: i32) -> HexCoordinates {
+        HexCoordinates::new(column - row.div_euclid(2), row)
+    }
+
+    /// Returns the HexCoordinates struct's axial `q` coordinate.
+    pub fn q(&self) -> i32 {
+        self.q
+    }
+
+    /// Returns the HexCoordinates struct's axial `r` coordinate.
+    pub fn r(&self) -> i32 {
+        self.r
+    }
+
+    /// Returns the HexCoordinates struct's offset column.
+    pub fn column(&self) -> i32 {
+        self.q + self.r.div_euclid(2)
+    }
+
+    /// Returns the HexCoordinates struct's row.
+    pub fn row(&self) -> i32 {
+        self.r
+    }
+}
+
+impl std::ops::Add<HexCoordinates> for HexCoordinates {
+    type Output = HexCoordinates;
+
+    fn add(self, increment: HexCoordinates) -> HexCoordinates {
+        HexCoordinates {
+            q: self.q + increment.q,
+            r: self.r + increment.r,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_round_trip() {
+        for (column, row) in [(0, 0), (3, 1), (2, 4), (-1, -3)] {
+            let coordinates = HexCoordinates::from_offset(column, row);
+            assert_eq!((coordinates.column(), coordinates.row()), (column, row));
+        }
+    }
+
+    #[test]
+    fn odd_rows_shift_east() {
+        // The cell north-east of the start of an even row is the start of the odd row above,
+        // and the cell north-east of that is the second cell of the even row above it.
+        let start = HexCoordinates::from_offset(0, 0);
+        let above = start + HexCoordinates::new(0, 1);
+        assert_eq!((above.column(), above.row()), (0, 1));
+        let above = above + HexCoordinates::new(0, 1);
+        assert_eq!((above.column(), above.row()), (1, 2));
+    }
+}
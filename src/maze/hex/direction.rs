@@ -0,0 +1,82 @@
+use crate::maze::hex::coordinates::HexCoordinates;
+
+/// Represents directions in a hex maze, whose cells are hexagons with a point at the top, so
+/// that each cell has neighbors to its east and west and on the diagonals above and below.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HexDirection {
+    NorthEast,
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// Associated functions to create and use a HexDirection enum.
+impl HexDirection {
+    /// All of the HexDirections, in clockwise order starting from NorthEast.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::NorthEast,
+        HexDirection::East,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+        HexDirection::West,
+        HexDirection::NorthWest,
+    ];
+
+    /// Returns the HexDirection's opposite.
+    pub fn opposite(&self) -> HexDirection {
+        match self {
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::East => HexDirection::West,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::West => HexDirection::East,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+        }
+    }
+
+    /// Returns the HexDirection as axial coordinates that could be added to another
+    /// HexCoordinates struct to get the coordinates in the direction.
+    pub fn coordinates(&self) -> HexCoordinates {
+        match self {
+            HexDirection::NorthEast => HexCoordinates::new(0, 1),
+            HexDirection::East => HexCoordinates::new(1, 0),
+            HexDirection::SouthEast => HexCoordinates::new(1, -1),
+            HexDirection::SouthWest => HexCoordinates::new(0, -1),
+            HexDirection::West => HexCoordinates::new(-1, 0),
+            HexDirection::NorthWest => HexCoordinates::new(-1, 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opposites_are_symmetric() {
+        for direction in HexDirection::ALL {
+            assert_ne!(direction.opposite(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+        }
+    }
+
+    #[test]
+    fn opposite_coordinates_cancel_out() {
+        for direction in HexDirection::ALL {
+            let coordinates = direction.coordinates() + direction.opposite().coordinates();
+            assert_eq!(coordinates, HexCoordinates::new(0, 0));
+        }
+    }
+
+    #[test]
+    fn going_around_returns_to_start() {
+        let coordinates = HexDirection::ALL
+            .iter()
+            .fold(HexCoordinates::new(3, 2), |coordinates, direction| {
+                coordinates + direction.coordinates()
+            });
+        assert_eq!(coordinates, HexCoordinates::new(3, 2));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::hex::cell::HexCell;
+use crate::maze::hex::coordinates::HexCoordinates;
+use crate::maze::hex::direction::HexDirection;
+
+pub mod cell;
+pub mod coordinates;
+pub mod direction;
+pub mod svg;
+
+/// Represents a maze of hexagonal cells, laid out in columns and rows with every odd row
+/// shifted half a cell east, so that the maze is roughly rectangular.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HexMaze {
+    columns: u32,
+    rows: u32,
+    cells: Vec<Option<HexCell>>,
+}
+
+/// Associated functions to create and use a HexMaze struct.
+impl HexMaze {
+    /// Creates a new, unpopulated HexMaze struct with the given dimensions.
+    pub fn new(columns: u32, rows: u32) -> HexMaze {
+        let cells = (0..columns * rows).map(|_i| None).collect();
+        HexMaze {
+            columns,
+            rows,
+            cells,
+        }
+    }
+
+    /// Returns the number of columns in the HexMaze struct.
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Returns the number of rows in the HexMaze struct.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Returns the number of cells in the HexMaze struct.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the coordinates of every cell in the HexMaze struct, by row, then by column.
+    pub fn coordinates(&self) -> impl Iterator<Item = HexCoordinates> {
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        (0..rows)
+            .flat_map(move |row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| HexCoordinates::from_offset(column, row))
+    }
+
+    /// Returns the given coordinates translated into an index into the HexMaze struct.
+    pub(crate) fn get_index(&self, coordinates: &HexCoordinates) -> usize {
+        if self.in_bounds(coordinates) {
+            (coordinates.row() * self.columns as i32 + coordinates.column()) as usize
+        } else {
+            panic!("Coordinates ({coordinates:?} out of bounds");
+        }
+    }
+
+    /// Returns the value of the HexMaze struct's cell at the given coordinates.
+    pub fn cell(&self, coordinates: &HexCoordinates) -> Option<HexCell> {
+        let index = self.get_index(coordinates);
+        self.cells[index]
+    }
+
+    /// Sets the value of the HexMaze struct's cell at the given coordinates.
+    pub fn set_cell(&mut self, cell: HexCell) {
+        let index = self.get_index(&cell.coordinates());
+        self.cells[index] = Some(cell);
+    }
+
+    /// Returns true if the given coordinates represent a location within the HexMaze
+    /// struct's bounds.
+    pub fn in_bounds(&self, coordinates: &HexCoordinates) -> bool {
+        (0..self.rows).contains(&(coordinates.row() as u32))
+            && (0..self.columns).contains(&(coordinates.column() as u32))
+    }
+
+    /// Sets the edge in the given direction of the cell at the given coordinates, populating
+    /// the cell if necessary. If the neighboring cell in that direction is in bounds, its
+    /// opposite edge is set to the same value.
+    pub fn set_edge(
+        &mut self,
+        coordinates: &HexCoordinates,
+        direction: &HexDirection,
+        cell_edge: CellEdge,
+    ) {
+        let mut cell = self
+            .cell(coordinates)
+            .unwrap_or_else(|| HexCell::new(*coordinates));
+        cell.set_edge(direction, Some(cell_edge));
+        self.set_cell(cell);
+
+        let neighbor_coordinates = *coordinates + direction.coordinates();
+        if self.in_bounds(&neighbor_coordinates) {
+            let mut neighbor = self
+                .cell(&neighbor_coordinates)
+                .unwrap_or_else(|| HexCell::new(neighbor_coordinates));
+            neighbor.set_edge(&direction.opposite(), Some(cell_edge));
+            self.set_cell(neighbor);
+        }
+    }
+
+    /// Populates every unpopulated cell, and assigns every unassigned edge: a
+    /// [CellEdge::Border] if it faces out of the HexMaze struct's bounds, otherwise a
+    /// [CellEdge::Wall].
+    pub fn close_unassigned_edges(&mut self) {
+        let all_coordinates: Vec<HexCoordinates> = self.coordinates().collect();
+        for coordinates in all_coordinates {
+            let mut cell = self
+                .cell(&coordinates)
+                .unwrap_or_else(|| HexCell::new(coordinates));
+            for direction in cell.unassigned_directions() {
+                if self.in_bounds(&(coordinates + direction.coordinates())) {
+                    cell.set_edge(&direction, Some(CellEdge::Wall));
+                } else {
+                    cell.set_edge(&direction, Some(CellEdge::Border));
+                }
+            }
+            self.set_cell(cell);
+        }
+    }
+
+    /// Turns the border edge in the given direction of the cell at the given coordinates
+    /// into a [CellEdge::Opening], an entrance or exit.
+    pub fn add_opening(&mut self, coordinates: &HexCoordinates, direction: &HexDirection) {
+        let mut cell = self
+            .cell(coordinates)
+            .unwrap_or_else(|| panic!("No cell at {coordinates:?}"));
+        assert_eq!(
+            cell.edge(direction),
+            Some(CellEdge::Border),
+            "Edge {direction:?} of {coordinates:?} is not a border"
+        );
+        cell.set_edge(direction, Some(CellEdge::Opening));
+        self.set_cell(cell);
+    }
+
+    /// Returns the coordinates and direction of every opening in the HexMaze struct, in row
+    /// order.
+    pub fn openings(&self) -> Vec<(HexCoordinates, HexDirection)> {
+        let mut openings = Vec::new();
+        for cell in self.cells.iter().flatten() {
+            for direction in HexDirection::ALL {
+                if cell.edge(&direction) == Some(CellEdge::Opening) {
+                    openings.push((cell.coordinates(), direction));
+                }
+            }
+        }
+        openings
+    }
+}
+
+impl IntoIterator for &HexMaze {
+    type Item = Option<HexCell>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.clone().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_by_row_then_column() {
+        let maze = HexMaze::new(2, 2);
+        let coordinates: Vec<(i32, i32)> = maze
+            .coordinates()
+            .map(|coordinates| (coordinates.column(), coordinates.row()))
+            .collect();
+        assert_eq!(coordinates, vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+        for (index, coordinates) in maze.coordinates().enumerate() {
+            assert_eq!(maze.get_index(&coordinates), index);
+        }
+    }
+
+    #[test]
+    fn in_bounds_uses_offset_columns() {
+        let maze = HexMaze::new(3, 3);
+        assert!(maze.in_bounds(&HexCoordinates::from_offset(2, 2)));
+        // Axial q of -1 is the first column of the third row.
+        assert!(maze.in_bounds(&HexCoordinates::new(-1, 2)));
+        assert!(!maze.in_bounds(&HexCoordinates::new(-1, 1)));
+        assert!(!maze.in_bounds(&HexCoordinates::from_offset(3, 0)));
+    }
+
+    #[test]
+    fn set_edge_sets_neighbor_edge() {
+        let mut maze = HexMaze::new(3, 3);
+        let coordinates = HexCoordinates::from_offset(1, 1);
+        maze.set_edge(&coordinates, &HexDirection::NorthWest, CellEdge::Passage);
+        let neighbor = maze
+            .cell(&HexCoordinates::from_offset(1, 2))
+            .expect("Neighbor was populated");
+        assert_eq!(
+            neighbor.edge(&HexDirection::SouthEast),
+            Some(CellEdge::Passage)
+        );
+    }
+
+    #[test]
+    fn close_unassigned_edges_borders_outside() {
+        let mut maze = HexMaze::new(2, 2);
+        maze.close_unassigned_edges();
+        // The first cell of the top row faces out of the maze to the north and west. The row
+        // is shifted east, so its neighbor to the south-west is still inside.
+        let cell = maze.cell(&HexCoordinates::from_offset(0, 1)).unwrap();
+        let borders: Vec<HexDirection> = HexDirection::ALL
+            .into_iter()
+            .filter(|direction| cell.edge(direction) == Some(CellEdge::Border))
+            .collect();
+        assert_eq!(
+            borders,
+            vec![
+                HexDirection::NorthEast,
+                HexDirection::West,
+                HexDirection::NorthWest,
+            ]
+        );
+        assert!(maze
+            .into_iter()
+            .flatten()
+            .all(|cell| cell.is_fully_assigned()));
+    }
+
+    #[test]
+    fn add_opening() {
+        let mut maze = HexMaze::new(2, 2);
+        maze.close_unassigned_edges();
+        let coordinates = HexCoordinates::from_offset(0, 1);
+        maze.add_opening(&coordinates, &HexDirection::West);
+        let cell = maze.cell(&coordinates).unwrap();
+        assert_eq!(cell.edge(&HexDirection::West), Some(CellEdge::Opening));
+        assert_eq!(maze.openings(), vec![(coordinates, HexDirection::West)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a border")]
+    fn add_opening_not_on_border() {
+        let mut maze = HexMaze::new(2, 2);
+        maze.close_unassigned_edges();
+        maze.add_opening(&HexCoordinates::from_offset(0, 0), &HexDirection::East);
+    }
+}
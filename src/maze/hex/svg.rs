@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::hex::coordinates::HexCoordinates;
+use crate::maze::hex::direction::HexDirection;
+use crate::maze::hex::HexMaze;
+
+/// Returns the hex maze drawn as an SVG document, with each cell a hexagon whose corners are
+/// the given size away from its center. Walls and borders are drawn as lines, and passages,
+/// openings and unassigned edges are left open.
+pub fn to_svg(maze: &HexMaze, size: f64) -> String {
+    let cell_width = 3f64.sqrt() * size;
+    // Leave room for the width of the lines around the edge of the drawing.
+    let margin = size / 4.0;
+    let width = (maze.columns() as f64 + 0.5) * cell_width + 2.0 * margin;
+    let height = (maze.rows().max(1) as f64 - 1.0) * 1.5 * size + 2.0 * size + 2.0 * margin;
+
+    let mut path = String::new();
+    for coordinates in maze.coordinates() {
+        let Some(cell) = maze.cell(&coordinates) else {
+            continue;
+        };
+        let (x, y) = center(maze, &coordinates, size, margin);
+        for (index, direction) in HexDirection::ALL.iter().enumerate() {
+            // Walls are drawn from the cell to their west, so that each is drawn once.
+            let is_drawn = match cell.edge(direction) {
+                Some(CellEdge::Border) => true,
+                Some(CellEdge::Wall) => index < 3,
+                _ => false,
+            };
+            if is_drawn {
+                let (x1, y1) = corner(x, y, size, index);
+                let (x2, y2) = corner(x, y, size, index + 1);
+                write!(path, "M{x1:.2} {y1:.2}L{x2:.2} {y2:.2}").unwrap();
+            }
+        }
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.2}\" height=\"{height:.2}\" \
+         viewBox=\"0 0 {width:.2} {height:.2}\">\
+         <path d=\"{path}\" fill=\"none\" stroke=\"black\" stroke-width=\"{:.2}\" \
+         stroke-linecap=\"round\"/></svg>\n",
+        size / 8.0
+    )
+}
+
+/// Returns the drawing's coordinates of the center of the cell at the given coordinates. The
+/// drawing's y axis points down, so the maze's first row is drawn at the bottom.
+fn center(maze: &HexMaze, coordinates: &HexCoordinates, size: f64, margin: f64) -> (f64, f64) {
+    let cell_width = 3f64.sqrt() * size;
+    let shift = coordinates.row().rem_euclid(2) as f64 / 2.0;
+    let x = margin + (coordinates.column() as f64 + 0.5 + shift) * cell_width;
+    let y = margin + size + (maze.rows() as f64 - 1.0 - coordinates.row() as f64) * 1.5 * size;
+    (x, y)
+}
+
+/// Returns the drawing's coordinates of the given corner of the hexagon with the given center.
+/// Corner 0 is the top, and the rest go clockwise, so the edge in [HexDirection::ALL] at a
+/// given index runs from the corner at that index to the next.
+fn corner(x: f64, y: f64, size: f64, index: usize) -> (f64, f64) {
+    let angle = (index as f64 * 60.0 - 90.0).to_radians();
+    (x + size * angle.cos(), y + size * angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_single_cell_as_hexagon() {
+        let mut maze = HexMaze::new(1, 1);
+        maze.close_unassigned_edges();
+        let svg = to_svg(&maze, 10.0);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches('M').count(), 6);
+        // The top corner of the cell, which is in the middle of the drawing.
+        assert!(svg.contains("M11.16 2.50L"));
+    }
+
+    #[test]
+    fn draws_shared_walls_once() {
+        let mut maze = HexMaze::new(2, 1);
+        maze.close_unassigned_edges();
+        // Ten borders around the outside, and the wall between the cells.
+        assert_eq!(to_svg(&maze, 10.0).matches('M').count(), 11);
+        maze.set_edge(
+            &HexCoordinates::from_offset(0, 0),
+            &HexDirection::East,
+            CellEdge::Passage,
+        );
+        assert_eq!(to_svg(&maze, 10.0).matches('M').count(), 10);
+    }
+
+    #[test]
+    fn leaves_openings_open() {
+        let mut maze = HexMaze::new(2, 1);
+        maze.close_unassigned_edges();
+        maze.add_opening(&HexCoordinates::from_offset(0, 0), &HexDirection::West);
+        maze.add_opening(&HexCoordinates::from_offset(1, 0), &HexDirection::East);
+        assert_eq!(to_svg(&maze, 10.0).matches('M').count(), 9);
+    }
+}
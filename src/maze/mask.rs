@@ -0,0 +1,415 @@
+use std::fmt;
+use std::io::Read;
+
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+
+/// Enumerates the ways loading a [Mask] can fail.
+#[derive(Debug)]
+pub enum MaskError {
+    /// The source has no cells.
+    Empty,
+    /// A line of text is not the same length as the first line.
+    RaggedLine { line: usize },
+    /// A line of text contains a character other than `#` or `.`.
+    InvalidCharacter { line: usize, character: char },
+    /// The active cells are not all connected to each other, or there are none.
+    Disconnected,
+    /// The PNG image could not be decoded.
+    Png(png::DecodingError),
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::Empty => write!(f, "mask has no cells"),
+            MaskError::RaggedLine { line } => {
+                write!(f, "line {line} is not the same length as line 1")
+            }
+            MaskError::InvalidCharacter { line, character } => {
+                write!(f, "line {line} contains {character:?}, expected '#' or '.'")
+            }
+            MaskError::Disconnected => write!(f, "mask's active cells are not connected"),
+            MaskError::Png(error) => write!(f, "invalid PNG: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MaskError::Png(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<png::DecodingError> for MaskError {
+    fn from(error: png::DecodingError) -> MaskError {
+        MaskError::Png(error)
+    }
+}
+
+/// Represents the shape of a maze, as the set of cells in a rectangular grid that are active.
+/// Inactive cells are holes that are never carved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    columns: u32,
+    rows: u32,
+    active: Vec<bool>,
+}
+
+/// Associated functions to create and use a Mask struct.
+impl Mask {
+    /// Creates a new Mask with the given dimensions, in which every cell is active.
+    pub fn new(columns: u32, rows: u32) -> Mask {
+        Mask {
+            columns,
+            rows,
+            active: vec![true; (columns * rows) as usize],
+        }
+    }
+
+    /// Creates a new Mask with the given dimensions, in which a cell is active if the given
+    /// function returns true for its coordinates.
+    pub fn from_fn<F: Fn(&Coordinates) -> bool>(columns: u32, rows: u32, is_active: F) -> Mask {
+        let mut mask = Mask::new(columns, rows);
+        for row in 0..rows as i32 {
+            for column in 0..columns as i32 {
+                let coordinates = Coordinates::new(column, row);
+                mask.set_active(&coordinates, is_active(&coordinates));
+            }
+        }
+        mask
+    }
+
+    /// Creates a new Mask from a grid of text, where each line is a row of cells, `#` is an
+    /// active cell and `.` is a masked-out cell. The first line is the northernmost row.
+    /// Empty lines at the end are ignored, but spaces are not trimmed from any line.
+    ///
+    /// Returns an error if the lines are not all the same length, if they contain any other
+    /// characters, or if the active cells are not connected.
+    pub fn from_text(text: &str) -> Result<Mask, MaskError> {
+        let mut lines: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+        let columns = lines.first().map(|line| line.chars().count()).unwrap_or(0);
+        if columns == 0 {
+            return Err(MaskError::Empty);
+        }
+
+        let rows = lines.len();
+        let mut mask = Mask::new(columns as u32, rows as u32);
+        for (index, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(MaskError::RaggedLine { line: index + 1 });
+            }
+            let row = (rows - 1 - index) as i32;
+            for (column, character) in line.chars().enumerate() {
+                let active = match character {
+                    '#' => true,
+                    '.' => false,
+                    _ => {
+                        return Err(MaskError::InvalidCharacter {
+                            line: index + 1,
+                            character,
+                        })
+                    }
+                };
+                mask.set_active(&Coordinates::new(column as i32, row), active);
+            }
+        }
+        mask.connected()
+    }
+
+    /// Creates a new Mask from a PNG image, where each pixel is a cell. Dark pixels are active
+    /// cells, and light or transparent pixels are masked-out cells. The top of the image is
+    /// the northernmost row.
+    ///
+    /// Returns an error if the image cannot be decoded, or if the active cells are not
+    /// connected.
+    pub fn from_png<R: Read>(reader: R) -> Result<Mask, MaskError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        if info.width == 0 || info.height == 0 {
+            return Err(MaskError::Empty);
+        }
+
+        let samples = info.color_type.samples();
+        let mut mask = Mask::new(info.width, info.height);
+        for y in 0..info.height as usize {
+            let line = &buffer[y * info.line_size..(y + 1) * info.line_size];
+            let row = (info.height as usize - 1 - y) as i32;
+            for (column, pixel) in line.chunks_exact(samples).enumerate() {
+                let (luma, alpha) = match info.color_type {
+                    png::ColorType::Grayscale => (pixel[0] as u32, 255),
+                    png::ColorType::GrayscaleAlpha => (pixel[0] as u32, pixel[1]),
+                    png::ColorType::Rgba => (
+                        (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3,
+                        pixel[3],
+                    ),
+                    // Indexed images are expanded to RGB by the decoder.
+                    _ => (
+                        (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3,
+                        255,
+                    ),
+                };
+                let active = alpha >= 128 && luma < 128;
+                mask.set_active(&Coordinates::new(column as i32, row), active);
+            }
+        }
+        mask.connected()
+    }
+
+    /// Returns the Mask if its active cells are connected, otherwise an error.
+    fn connected(self) -> Result<Mask, MaskError> {
+        if self.is_connected() {
+            Ok(self)
+        } else {
+            Err(MaskError::Disconnected)
+        }
+    }
+
+    /// Returns the number of columns in the Mask struct.
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Returns the number of rows in the Mask struct.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    fn get_index(&self, coordinates: &Coordinates) -> Option<usize> {
+        if (0..self.rows).contains(&(coordinates.row() as u32))
+            && (0..self.columns).contains(&(coordinates.column() as u32))
+        {
+            Some((coordinates.row() * (self.columns as i32) + coordinates.column()) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the cell at the given coordinates is active. Cells outside the Mask
+    /// struct's bounds are never active.
+    pub fn is_active(&self, coordinates: &Coordinates) -> bool {
+        self.get_index(coordinates)
+            .map(|index| self.active[index])
+            .unwrap_or(false)
+    }
+
+    /// Sets whether the cell at the given coordinates is active.
+    pub fn set_active(&mut self, coordinates: &Coordinates, active: bool) {
+        match self.get_index(coordinates) {
+            Some(index) => self.active[index] = active,
+            None => panic!("Coordinates ({coordinates:?} out of bounds"),
+        }
+    }
+
+    /// Returns the number of active cells in the Mask struct.
+    pub fn active_count(&self) -> usize {
+        self.active.iter().filter(|active| **active).count()
+    }
+
+    /// Returns true if every active cell can be reached from every other active cell by
+    /// moving between neighboring active cells. A Mask with no active cells is not connected.
+    pub fn is_connected(&self) -> bool {
+        let Some(first) = self.active.iter().position(|active| *active) else {
+            return false;
+        };
+        let mut reached = vec![false; self.active.len()];
+        reached[first] = true;
+        let mut stack = vec![Coordinates::new(
+            (first % self.columns as usize) as i32,
+            (first / self.columns as usize) as i32,
+        )];
+        let mut count = 0;
+        while let Some(coordinates) = stack.pop() {
+            count += 1;
+            for direction in Direction::ALL {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if let Some(index) = self.get_index(&neighbor_coordinates) {
+                    if self.active[index] && !reached[index] {
+                        reached[index] = true;
+                        stack.push(neighbor_coordinates);
+                    }
+                }
+            }
+        }
+        count == self.active_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_mask_is_all_active() {
+        let mask = Mask::new(4, 3);
+        assert_eq!(mask.active_count(), 12);
+        assert!(mask.is_active(&Coordinates::new(3, 2)));
+    }
+
+    #[test]
+    fn out_of_bounds_is_not_active() {
+        let mask = Mask::new(4, 3);
+        assert!(!mask.is_active(&Coordinates::new(4, 0)));
+        assert!(!mask.is_active(&Coordinates::new(0, -1)));
+    }
+
+    #[test]
+    fn set_active() {
+        let mut mask = Mask::new(4, 3);
+        let coordinates = Coordinates::new(1, 2);
+        mask.set_active(&coordinates, false);
+        assert!(!mask.is_active(&coordinates));
+        assert_eq!(mask.active_count(), 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn set_active_not_in_bounds() {
+        Mask::new(4, 3).set_active(&Coordinates::new(0, 3), false);
+    }
+
+    #[test]
+    fn from_fn() {
+        let mask = Mask::from_fn(4, 4, |coordinates| {
+            coordinates.column() >= coordinates.row()
+        });
+        assert_eq!(mask.active_count(), 10);
+        assert!(mask.is_active(&Coordinates::new(3, 0)));
+        assert!(!mask.is_active(&Coordinates::new(0, 3)));
+    }
+
+    #[test]
+    fn from_text() {
+        let mask = Mask::from_text("##.\n.##\n").unwrap();
+        assert_eq!((mask.columns(), mask.rows()), (3, 2));
+        assert!(mask.is_active(&Coordinates::new(0, 1)));
+        assert!(!mask.is_active(&Coordinates::new(2, 1)));
+        assert!(!mask.is_active(&Coordinates::new(0, 0)));
+        assert!(mask.is_active(&Coordinates::new(2, 0)));
+    }
+
+    #[test]
+    fn from_text_with_crlf() {
+        let mask = Mask::from_text("#.\r\n##\r\n").unwrap();
+        assert_eq!((mask.columns(), mask.rows()), (2, 2));
+        assert_eq!(mask.active_count(), 3);
+    }
+
+    #[test]
+    fn from_text_empty() {
+        assert!(matches!(Mask::from_text("\n"), Err(MaskError::Empty)));
+    }
+
+    #[test]
+    fn from_text_ragged() {
+        assert!(matches!(
+            Mask::from_text("###\n##\n"),
+            Err(MaskError::RaggedLine { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn from_text_ignores_empty_lines_at_end() {
+        let mask = Mask::from_text("##\n#.\n\n\r\n").unwrap();
+        assert_eq!((mask.columns(), mask.rows()), (2, 2));
+    }
+
+    #[test]
+    fn from_text_keeps_trailing_spaces() {
+        assert!(matches!(
+            Mask::from_text("#.\n## \n"),
+            Err(MaskError::RaggedLine { line: 2 })
+        ));
+        assert!(matches!(
+            Mask::from_text("#. \n## \n"),
+            Err(MaskError::InvalidCharacter {
+                line: 1,
+                character: ' '
+            })
+        ));
+    }
+
+    #[test]
+    fn from_text_invalid_character() {
+        assert!(matches!(
+            Mask::from_text("##\n#x\n"),
+            Err(MaskError::InvalidCharacter {
+                line: 2,
+                character: 'x'
+            })
+        ));
+    }
+
+    #[test]
+    fn from_text_disconnected() {
+        assert!(matches!(
+            Mask::from_text("#.#\n"),
+            Err(MaskError::Disconnected)
+        ));
+    }
+
+    fn encode_png(width: u32, height: u32, color_type: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[test]
+    fn from_grayscale_png() {
+        let bytes = encode_png(3, 2, png::ColorType::Grayscale, &[0, 0, 255, 255, 0, 0]);
+        let mask = Mask::from_png(bytes.as_slice()).unwrap();
+        assert_eq!(mask, Mask::from_text("##.\n.##\n").unwrap());
+    }
+
+    #[test]
+    fn from_rgba_png_treats_transparent_as_masked_out() {
+        let bytes = encode_png(2, 1, png::ColorType::Rgba, &[0, 0, 0, 255, 0, 0, 0, 0]);
+        let mask = Mask::from_png(bytes.as_slice()).unwrap();
+        assert_eq!(mask, Mask::from_text("#.\n").unwrap());
+    }
+
+    #[test]
+    fn from_invalid_png() {
+        assert!(matches!(
+            Mask::from_png(&b"not a png"[..]),
+            Err(MaskError::Png(_))
+        ));
+    }
+
+    #[test]
+    fn is_connected() {
+        let mask = Mask::from_fn(5, 5, |coordinates| {
+            coordinates.row() == 2 || coordinates.column() == 2
+        });
+        assert!(mask.is_connected());
+    }
+
+    #[test]
+    fn is_not_connected() {
+        let mask = Mask::from_fn(5, 5, |coordinates| coordinates.column() != 2);
+        assert!(!mask.is_connected());
+    }
+
+    #[test]
+    fn empty_mask_is_not_connected() {
+        let mask = Mask::from_fn(5, 5, |_coordinates| false);
+        assert!(!mask.is_connected());
+    }
+}
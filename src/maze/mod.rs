@@ -1,31 +1,85 @@
 use crate::maze::cell::Cell;
+use crate::maze::cell_edge::CellEdge;
 use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::mask::Mask;
 
 pub mod cell;
 pub mod cell_edge;
 pub mod coordinates;
 pub mod direction;
+pub mod hex;
+pub mod mask;
 
-/// Represents a maze as a two-dimensional vector of Cells.
-#[derive(Debug)]
+/// Represents a maze as a grid of Cells, with one or more levels of columns and rows. A maze
+/// created from a [Mask] only has cells where the mask is active, and the rest of the grid is
+/// left as holes.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Maze {
     columns: u32,
     rows: u32,
+    levels: u32,
     cells: Vec<Option<Cell>>,
+    mask: Option<Mask>,
+    entrance: Option<(Coordinates, Direction)>,
+    exit: Option<(Coordinates, Direction)>,
 }
 
 /// Associated functions to create and use a Maze struct.
 impl Maze {
-    /// Creates a new, unpopulated Maze struct with the given dimensions.
+    /// Creates a new, unpopulated, flat Maze struct with the given dimensions.
     pub fn new(columns: u32, rows: u32) -> Maze {
-        let cells = (0..columns * rows).map(|_i| None).collect();
+        Maze::with_levels(columns, rows, 1)
+    }
+
+    /// Creates a new, unpopulated Maze struct with the given dimensions, whose levels are
+    /// joined by [Direction::Above] and [Direction::Below].
+    pub fn with_levels(columns: u32, rows: u32, levels: u32) -> Maze {
+        let cells = (0..columns * rows * levels).map(|_i| None).collect();
         Maze {
             columns,
             rows,
+            levels,
             cells,
+            mask: None,
+            entrance: None,
+            exit: None,
         }
     }
 
+    /// Creates a new, unpopulated Maze struct with the dimensions and shape of the given mask.
+    /// The mask's active cells must be connected, so that the maze can be solved.
+    pub fn with_mask(mask: Mask) -> Maze {
+        assert!(mask.is_connected(), "Mask's active cells are not connected");
+        let mut maze = Maze::new(mask.columns(), mask.rows());
+        maze.mask = Some(mask);
+        maze
+    }
+
+    /// Returns the Maze struct's mask, if it has one.
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    /// Returns the number of cells in the Maze struct that can be populated.
+    pub fn active_count(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.active_count(),
+            None => self.grid_size(),
+        }
+    }
+
+    /// Returns the number of locations in the Maze struct's grid, active or not.
+    pub(crate) fn grid_size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the Maze struct's cells, by level, then by row, then by column, so that parts
+    /// of the grid can be populated in place.
+    pub(crate) fn cells_mut(&mut self) -> &mut [Option<Cell>] {
+        &mut self.cells
+    }
+
     /// Returns the number of columns in the Maze struct.
     pub fn columns(&self) -> u32 {
         self.columns
@@ -36,11 +90,47 @@ impl Maze {
         self.rows
     }
 
+    /// Returns the number of levels in the Maze struct.
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Returns the directions that can lead from one cell to another: the compass directions
+    /// in a flat maze, and Above and Below as well in a maze with more than one level.
+    pub fn directions(&self) -> &'static [Direction] {
+        if self.levels > 1 {
+            &Direction::ALL_LEVELS
+        } else {
+            &Direction::ALL
+        }
+    }
+
+    /// Returns a new Cell with the given coordinates, with no assigned edges in any of the
+    /// Maze struct's directions.
+    pub fn new_cell(&self, coordinates: Coordinates) -> Cell {
+        if self.levels > 1 {
+            Cell::new_multilevel(coordinates)
+        } else {
+            Cell::new(coordinates)
+        }
+    }
+
+    /// Returns the coordinates of every location in the Maze struct's grid, active or not, by
+    /// level, then by row, then by column.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        (0..self.levels as i32).flat_map(move |level| {
+            (0..rows).flat_map(move |row| {
+                (0..columns).map(move |column| Coordinates::with_level(column, row, level))
+            })
+        })
+    }
+
     /// Returns the given maze coordinates translated into an index into the Maze struct.
-    /// (private)
-    fn get_index(&self, coordinates: &Coordinates) -> usize {
+    pub(crate) fn get_index(&self, coordinates: &Coordinates) -> usize {
         if self.in_bounds(coordinates) {
-            (coordinates.row() * (self.columns as i32) + coordinates.column()) as usize
+            ((coordinates.level() * self.rows as i32 + coordinates.row()) * self.columns as i32
+                + coordinates.column()) as usize
         } else {
             panic!("Coordinates ({coordinates:?} out of bounds");
         }
@@ -62,6 +152,116 @@ impl Maze {
     pub fn in_bounds(&self, coordinates: &Coordinates) -> bool {
         (0..self.rows).contains(&(coordinates.row() as u32))
             && (0..self.columns).contains(&(coordinates.column() as u32))
+            && (0..self.levels).contains(&(coordinates.level() as u32))
+    }
+
+    /// Returns true if the given coordinates are in bounds and not masked out, so the cell
+    /// there can be part of the maze.
+    pub fn is_active(&self, coordinates: &Coordinates) -> bool {
+        match &self.mask {
+            Some(mask) => coordinates.level() == 0 && mask.is_active(coordinates),
+            None => self.in_bounds(coordinates),
+        }
+    }
+
+    /// Sets the edge in the given direction of the cell at the given coordinates, populating
+    /// the cell if necessary. If the neighboring cell in that direction is active, its
+    /// opposite edge is set to the same value.
+    pub fn set_edge(
+        &mut self,
+        coordinates: &Coordinates,
+        direction: &Direction,
+        cell_edge: CellEdge,
+    ) {
+        let mut cell = self
+            .cell(coordinates)
+            .unwrap_or_else(|| self.new_cell(*coordinates));
+        cell.set_edge(direction, Some(cell_edge));
+        self.set_cell(cell);
+
+        let neighbor_coordinates = *coordinates + direction.coordinates();
+        if self.is_active(&neighbor_coordinates) {
+            let mut neighbor = self
+                .cell(&neighbor_coordinates)
+                .unwrap_or_else(|| self.new_cell(neighbor_coordinates));
+            neighbor.set_edge(&direction.opposite(), Some(cell_edge));
+            self.set_cell(neighbor);
+        }
+    }
+
+    /// Populates every unpopulated active cell, and assigns every unassigned edge: a
+    /// [CellEdge::Border] if it faces out of the Maze struct's bounds or into a masked-out
+    /// cell, otherwise a [CellEdge::Wall].
+    pub fn close_unassigned_edges(&mut self) {
+        let all_coordinates: Vec<Coordinates> = self.coordinates().collect();
+        for coordinates in all_coordinates {
+            if !self.is_active(&coordinates) {
+                continue;
+            }
+            let mut cell = self
+                .cell(&coordinates)
+                .unwrap_or_else(|| self.new_cell(coordinates));
+            for direction in cell.unassigned_directions() {
+                if self.is_active(&(coordinates + direction.coordinates())) {
+                    cell.set_edge(&direction, Some(CellEdge::Wall));
+                } else {
+                    cell.set_edge(&direction, Some(CellEdge::Border));
+                }
+            }
+            self.set_cell(cell);
+        }
+    }
+
+    /// Turns the border edge in the given direction of the cell at the given coordinates
+    /// into a [CellEdge::Opening], an entrance or exit.
+    pub fn add_opening(&mut self, coordinates: &Coordinates, direction: &Direction) {
+        let mut cell = self
+            .cell(coordinates)
+            .unwrap_or_else(|| panic!("No cell at {coordinates:?}"));
+        assert_eq!(
+            cell.edge(direction),
+            Some(CellEdge::Border),
+            "Edge {direction:?} of {coordinates:?} is not a border"
+        );
+        cell.set_edge(direction, Some(CellEdge::Opening));
+        self.set_cell(cell);
+    }
+
+    /// Adds an opening to the border edge in the given direction of the cell at the given
+    /// coordinates, and makes it the Maze struct's entrance.
+    pub fn set_entrance(&mut self, coordinates: &Coordinates, direction: &Direction) {
+        self.add_opening(coordinates, direction);
+        self.entrance = Some((*coordinates, *direction));
+    }
+
+    /// Adds an opening to the border edge in the given direction of the cell at the given
+    /// coordinates, and makes it the Maze struct's exit.
+    pub fn set_exit(&mut self, coordinates: &Coordinates, direction: &Direction) {
+        self.add_opening(coordinates, direction);
+        self.exit = Some((*coordinates, *direction));
+    }
+
+    /// Returns the coordinates and direction of the Maze struct's entrance, if it has one.
+    pub fn entrance(&self) -> Option<(Coordinates, Direction)> {
+        self.entrance
+    }
+
+    /// Returns the coordinates and direction of the Maze struct's exit, if it has one.
+    pub fn exit(&self) -> Option<(Coordinates, Direction)> {
+        self.exit
+    }
+
+    /// Returns the coordinates and direction of every opening in the Maze struct, in row order.
+    pub fn openings(&self) -> Vec<(Coordinates, Direction)> {
+        let mut openings = Vec::new();
+        for cell in self.cells.iter().flatten() {
+            for direction in Direction::ALL {
+                if cell.edge(&direction) == Some(CellEdge::Opening) {
+                    openings.push((cell.coordinates(), direction));
+                }
+            }
+        }
+        openings
     }
 }
 
@@ -77,8 +277,6 @@ impl IntoIterator for &Maze {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::maze::cell_edge::CellEdge;
-    use crate::maze::direction::Direction;
 
     #[test]
     fn columns() {
@@ -256,4 +454,161 @@ mod tests {
         let coordinates = Coordinates::new(column, row);
         cell_grid.cell(&coordinates);
     }
+
+    #[test]
+    fn set_edge_sets_both_sides() {
+        let mut cell_grid = Maze::new(4, 3);
+        let coordinates = Coordinates::new(1, 1);
+        cell_grid.set_edge(&coordinates, &Direction::North, CellEdge::Passage);
+        let cell = cell_grid.cell(&coordinates).unwrap();
+        let neighbor = cell_grid.cell(&Coordinates::new(1, 2)).unwrap();
+        assert_eq!(cell.edge(&Direction::North), Some(CellEdge::Passage));
+        assert_eq!(neighbor.edge(&Direction::South), Some(CellEdge::Passage));
+    }
+
+    #[test]
+    fn set_edge_at_border_sets_one_side() {
+        let mut cell_grid = Maze::new(4, 3);
+        let coordinates = Coordinates::new(0, 0);
+        cell_grid.set_edge(&coordinates, &Direction::West, CellEdge::Border);
+        let cell = cell_grid.cell(&coordinates).unwrap();
+        assert_eq!(cell.edge(&Direction::West), Some(CellEdge::Border));
+    }
+
+    #[test]
+    fn close_unassigned_edges_assigns_walls_and_borders() {
+        let mut cell_grid = Maze::new(2, 1);
+        cell_grid.close_unassigned_edges();
+        let cell = cell_grid.cell(&Coordinates::new(0, 0)).unwrap();
+        assert!(cell.is_fully_assigned());
+        assert_eq!(cell.edge(&Direction::East), Some(CellEdge::Wall));
+        assert_eq!(cell.edge(&Direction::West), Some(CellEdge::Border));
+        assert_eq!(cell.edge(&Direction::North), Some(CellEdge::Border));
+    }
+
+    #[test]
+    fn close_unassigned_edges_keeps_assigned_edges() {
+        let mut cell_grid = Maze::new(2, 1);
+        let coordinates = Coordinates::new(0, 0);
+        cell_grid.set_edge(&coordinates, &Direction::East, CellEdge::Passage);
+        cell_grid.close_unassigned_edges();
+        let cell = cell_grid.cell(&coordinates).unwrap();
+        assert_eq!(cell.edge(&Direction::East), Some(CellEdge::Passage));
+    }
+
+    #[test]
+    fn with_mask_has_mask_dimensions() {
+        let cell_grid = Maze::with_mask(Mask::new(7, 3));
+        assert_eq!(cell_grid.columns(), 7);
+        assert_eq!(cell_grid.rows(), 3);
+        assert_eq!(cell_grid.active_count(), 21);
+    }
+
+    #[test]
+    #[should_panic(expected = "not connected")]
+    fn with_disconnected_mask() {
+        Maze::with_mask(Mask::from_fn(3, 3, |coordinates| coordinates.row() != 1));
+    }
+
+    #[test]
+    fn masked_out_cell_is_not_active() {
+        let mut mask = Mask::new(3, 3);
+        mask.set_active(&Coordinates::new(0, 0), false);
+        let cell_grid = Maze::with_mask(mask);
+        assert!(!cell_grid.is_active(&Coordinates::new(0, 0)));
+        assert!(cell_grid.is_active(&Coordinates::new(1, 0)));
+        assert!(!cell_grid.is_active(&Coordinates::new(3, 0)));
+        assert_eq!(cell_grid.active_count(), 8);
+    }
+
+    #[test]
+    fn close_unassigned_edges_borders_masked_out_cells() {
+        let mut mask = Mask::new(2, 1);
+        mask.set_active(&Coordinates::new(1, 0), false);
+        let mut cell_grid = Maze::with_mask(mask);
+        cell_grid.close_unassigned_edges();
+        let cell = cell_grid.cell(&Coordinates::new(0, 0)).unwrap();
+        assert_eq!(cell.edge(&Direction::East), Some(CellEdge::Border));
+        assert_eq!(cell_grid.cell(&Coordinates::new(1, 0)), None);
+    }
+
+    #[test]
+    fn add_opening() {
+        let mut cell_grid = Maze::new(2, 2);
+        cell_grid.close_unassigned_edges();
+        let coordinates = Coordinates::new(1, 0);
+        cell_grid.add_opening(&coordinates, &Direction::South);
+        let cell = cell_grid.cell(&coordinates).unwrap();
+        assert_eq!(cell.edge(&Direction::South), Some(CellEdge::Opening));
+        assert_eq!(cell_grid.openings(), vec![(coordinates, Direction::South)]);
+    }
+
+    #[test]
+    fn entrance_and_exit() {
+        let mut cell_grid = Maze::new(2, 2);
+        cell_grid.close_unassigned_edges();
+        assert_eq!(cell_grid.entrance(), None);
+        cell_grid.set_entrance(&Coordinates::new(0, 1), &Direction::North);
+        cell_grid.set_exit(&Coordinates::new(1, 0), &Direction::South);
+        assert_eq!(
+            cell_grid.entrance(),
+            Some((Coordinates::new(0, 1), Direction::North))
+        );
+        assert_eq!(
+            cell_grid.exit(),
+            Some((Coordinates::new(1, 0), Direction::South))
+        );
+        assert_eq!(cell_grid.openings().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a border")]
+    fn add_opening_not_on_border() {
+        let mut cell_grid = Maze::new(2, 2);
+        cell_grid.close_unassigned_edges();
+        cell_grid.add_opening(&Coordinates::new(0, 0), &Direction::East);
+    }
+
+    #[test]
+    fn with_levels() {
+        let cell_grid = Maze::with_levels(3, 2, 4);
+        assert_eq!(cell_grid.levels(), 4);
+        assert_eq!(cell_grid.active_count(), 24);
+        assert!(cell_grid.in_bounds(&Coordinates::with_level(2, 1, 3)));
+        assert!(!cell_grid.in_bounds(&Coordinates::with_level(2, 1, 4)));
+        assert_eq!(cell_grid.directions(), &Direction::ALL_LEVELS);
+        assert_eq!(Maze::new(3, 2).directions(), &Direction::ALL);
+    }
+
+    #[test]
+    fn coordinates_by_level_then_row() {
+        let cell_grid = Maze::with_levels(2, 2, 2);
+        let coordinates: Vec<Coordinates> = cell_grid.coordinates().collect();
+        assert_eq!(coordinates.len(), 8);
+        for (index, coordinates) in coordinates.iter().enumerate() {
+            assert_eq!(cell_grid.get_index(coordinates), index);
+        }
+        assert_eq!(coordinates[5], Coordinates::with_level(1, 0, 1));
+    }
+
+    #[test]
+    fn set_edge_above_sets_edge_below() {
+        let mut cell_grid = Maze::with_levels(2, 2, 2);
+        let coordinates = Coordinates::new(1, 1);
+        cell_grid.set_edge(&coordinates, &Direction::Above, CellEdge::Passage);
+        let above = cell_grid.cell(&(coordinates + Coordinates::ABOVE)).unwrap();
+        assert_eq!(above.edge(&Direction::Below), Some(CellEdge::Passage));
+        assert_eq!(above.unassigned_directions().len(), 5);
+    }
+
+    #[test]
+    fn close_unassigned_edges_borders_top_and_bottom_levels() {
+        let mut cell_grid = Maze::with_levels(1, 1, 2);
+        cell_grid.close_unassigned_edges();
+        let bottom = cell_grid.cell(&Coordinates::new(0, 0)).unwrap();
+        assert_eq!(bottom.edge(&Direction::Below), Some(CellEdge::Border));
+        assert_eq!(bottom.edge(&Direction::Above), Some(CellEdge::Wall));
+        let top = cell_grid.cell(&Coordinates::with_level(0, 0, 1)).unwrap();
+        assert_eq!(top.edge(&Direction::Above), Some(CellEdge::Border));
+    }
 }
@@ -0,0 +1,130 @@
+use rand::RngCore;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::Maze;
+use crate::maze_generator::{random_coordinates, random_neighbor, wilson, MazeAlgorithm};
+
+/// Carves a maze with the Aldous-Broder algorithm: a random walk wanders the grid, carving a
+/// passage whenever it steps into a cell it has not visited before. Like [Wilson](super::Wilson),
+/// the result is sampled uniformly from every possible perfect maze of the given size.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AldousBroder;
+
+impl MazeAlgorithm for AldousBroder {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut in_maze = vec![false; maze.grid_size()];
+        let target = maze.active_count();
+        random_walk(maze, &mut in_maze, target, rng);
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Carves a uniform maze by starting with the Aldous-Broder algorithm, and switching to
+/// Wilson's algorithm once a threshold fraction of the cells has been visited.
+///
+/// Aldous-Broder is fast while most cells are unvisited, and Wilson's is fast once most cells
+/// are in the maze, so the hybrid is quicker than either on large grids.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AldousBroderWilson {
+    threshold: f64,
+}
+
+impl AldousBroderWilson {
+    /// Creates a new AldousBroderWilson that switches to Wilson's algorithm once the given
+    /// fraction of cells (between 0.0 and 1.0) has been visited.
+    pub fn new(threshold: f64) -> AldousBroderWilson {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "Threshold {threshold} is not between 0 and 1"
+        );
+        AldousBroderWilson { threshold }
+    }
+
+    /// Returns the fraction of cells visited before switching to Wilson's algorithm.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+/// Switches to Wilson's algorithm once a third of the cells have been visited.
+impl Default for AldousBroderWilson {
+    fn default() -> AldousBroderWilson {
+        AldousBroderWilson::new(1.0 / 3.0)
+    }
+}
+
+impl MazeAlgorithm for AldousBroderWilson {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut in_maze = vec![false; maze.grid_size()];
+        let target = ((maze.active_count() as f64 * self.threshold).ceil() as usize).max(1);
+        random_walk(maze, &mut in_maze, target, rng);
+        wilson::add_remaining_cells(maze, &mut in_maze, rng);
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Walks randomly from a random cell until the given number of cells is in the maze,
+/// carving a passage into each cell the first time it is visited.
+fn random_walk(maze: &mut Maze, in_maze: &mut [bool], target: usize, rng: &mut dyn RngCore) {
+    let mut coordinates = random_coordinates(maze, rng);
+    in_maze[maze.get_index(&coordinates)] = true;
+    let mut visited = 1;
+    while visited < target {
+        let (direction, neighbor_coordinates) = random_neighbor(maze, &coordinates, rng);
+        let index = maze.get_index(&neighbor_coordinates);
+        if !in_maze[index] {
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            in_maze[index] = true;
+            visited += 1;
+        }
+        coordinates = neighbor_coordinates;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn aldous_broder_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut maze = Maze::new(14, 9);
+        AldousBroder.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn hybrid_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut maze = Maze::new(14, 9);
+        AldousBroderWilson::default().carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn hybrid_carves_perfect_maze_at_extreme_thresholds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        for threshold in [0.0, 1.0] {
+            let mut maze = Maze::new(8, 5);
+            AldousBroderWilson::new(threshold).carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn carves_single_cell_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut maze = Maze::new(1, 1);
+        AldousBroder.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    #[should_panic(expected = "not between 0 and 1")]
+    fn hybrid_rejects_invalid_threshold() {
+        AldousBroderWilson::new(1.5);
+    }
+}
@@ -0,0 +1,134 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::maze::direction::Direction;
+
+/// Controls how a generator chooses which direction to carve in, in place of a uniform
+/// random choice.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bias {
+    horizontal: f64,
+    straightness: f64,
+}
+
+/// Associated functions to create and use a Bias struct.
+impl Bias {
+    /// Creates a new Bias.
+    ///
+    /// `horizontal` (between 0.0 and 1.0) is the weight given to East and West, with North and
+    /// South getting the rest; 0.5 weights all directions equally. `straightness` (between
+    /// 0.0 and 1.0) is the probability of continuing in the same direction as the last
+    /// passage, whenever that is possible.
+    pub fn new(horizontal: f64, straightness: f64) -> Bias {
+        assert!(
+            (0.0..=1.0).contains(&horizontal),
+            "Horizontal bias {horizontal} is not between 0 and 1"
+        );
+        assert!(
+            (0.0..=1.0).contains(&straightness),
+            "Straightness {straightness} is not between 0 and 1"
+        );
+        Bias {
+            horizontal,
+            straightness,
+        }
+    }
+
+    /// Returns the weight given to East and West.
+    pub fn horizontal(&self) -> f64 {
+        self.horizontal
+    }
+
+    /// Returns the probability of continuing in the same direction.
+    pub fn straightness(&self) -> f64 {
+        self.straightness
+    }
+
+    /// Returns one of the given directions, chosen according to the Bias. `previous` is the
+    /// direction of the passage that led to the cell, if there was one.
+    pub fn choose<R: Rng + ?Sized>(
+        &self,
+        directions: &[Direction],
+        previous: Option<Direction>,
+        rng: &mut R,
+    ) -> Direction {
+        assert!(!directions.is_empty(), "No directions to choose from");
+        if let Some(previous) = previous {
+            if directions.contains(&previous) && rng.gen_bool(self.straightness) {
+                return previous;
+            }
+        }
+        let weight = |direction: &Direction| match direction {
+            Direction::East | Direction::West => self.horizontal,
+            Direction::North | Direction::South => 1.0 - self.horizontal,
+            // Stairs are neither horizontal nor vertical on the page, so they aren't biased.
+            Direction::Above | Direction::Below => 0.5,
+        };
+        match directions.choose_weighted(rng, weight) {
+            Ok(direction) => *direction,
+            // Every direction has zero weight, so none is preferred.
+            Err(_) => directions[rng.gen_range(0..directions.len() as u32) as usize],
+        }
+    }
+}
+
+/// Weights every direction equally, and never prefers going straight.
+impl Default for Bias {
+    fn default() -> Bias {
+        Bias::new(0.5, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn full_horizontal_bias_chooses_horizontal() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bias = Bias::new(1.0, 0.0);
+        for _ in 0..50 {
+            let direction = bias.choose(&Direction::ALL, None, &mut rng);
+            assert!(matches!(direction, Direction::East | Direction::West));
+        }
+    }
+
+    #[test]
+    fn full_horizontal_bias_falls_back_to_vertical() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bias = Bias::new(1.0, 0.0);
+        let directions = [Direction::North, Direction::South];
+        for _ in 0..50 {
+            assert!(directions.contains(&bias.choose(&directions, None, &mut rng)));
+        }
+    }
+
+    #[test]
+    fn full_straightness_continues_straight() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bias = Bias::new(0.5, 1.0);
+        for _ in 0..50 {
+            let direction = bias.choose(&Direction::ALL, Some(Direction::South), &mut rng);
+            assert_eq!(direction, Direction::South);
+        }
+    }
+
+    #[test]
+    fn straightness_ignores_unavailable_previous_direction() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bias = Bias::new(0.5, 1.0);
+        let directions = [Direction::East, Direction::West];
+        for _ in 0..50 {
+            let direction = bias.choose(&directions, Some(Direction::North), &mut rng);
+            assert!(directions.contains(&direction));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not between 0 and 1")]
+    fn rejects_invalid_straightness() {
+        Bias::new(0.5, -0.1);
+    }
+}
@@ -0,0 +1,211 @@
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{carve_by_level, MazeAlgorithm};
+
+/// Carves a maze with the Binary Tree algorithm: every cell carves a passage in one of two
+/// directions, chosen at random. Very fast, and needs no memory beyond the maze itself, but
+/// every path leads towards the bias corner, and the two sides meeting there are unbroken
+/// corridors. It cannot carve a masked maze, and carves levels with [carve_by_level].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BinaryTree {
+    vertical: Direction,
+    horizontal: Direction,
+}
+
+impl BinaryTree {
+    /// Creates a new BinaryTree biased towards the corner between the given directions, one
+    /// of which must be North or South, and the other East or West.
+    pub fn new(vertical: Direction, horizontal: Direction) -> BinaryTree {
+        assert!(
+            matches!(vertical, Direction::North | Direction::South)
+                && matches!(horizontal, Direction::East | Direction::West),
+            "{vertical:?} and {horizontal:?} do not make a corner"
+        );
+        BinaryTree {
+            vertical,
+            horizontal,
+        }
+    }
+
+    /// Returns the directions of the BinaryTree's bias corner, vertical first.
+    pub fn bias(&self) -> (Direction, Direction) {
+        (self.vertical, self.horizontal)
+    }
+}
+
+/// Biased towards the north-east corner.
+impl Default for BinaryTree {
+    fn default() -> BinaryTree {
+        BinaryTree::new(Direction::North, Direction::East)
+    }
+}
+
+impl MazeAlgorithm for BinaryTree {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        assert!(
+            maze.mask().is_none(),
+            "BinaryTree cannot carve a masked maze"
+        );
+        if maze.levels() > 1 {
+            carve_by_level(self, maze, rng);
+            return;
+        }
+        for row in 0..maze.rows() as i32 {
+            for column in 0..maze.columns() as i32 {
+                let coordinates = Coordinates::new(column, row);
+                let directions: Vec<Direction> = [self.vertical, self.horizontal]
+                    .into_iter()
+                    .filter(|direction| maze.in_bounds(&(coordinates + direction.coordinates())))
+                    .collect();
+                let direction = match directions.len() {
+                    0 => continue,
+                    1 => directions[0],
+                    _ => directions[rng.gen_range(0..2u32) as usize],
+                };
+                maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            }
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Carves a maze with the Sidewinder algorithm: each line of cells across the bias direction
+/// is split into random runs of cells joined by passages, and each run carves one passage in
+/// the bias direction. Like [BinaryTree] it makes a single pass, but only one side of the maze
+/// is an unbroken corridor. It cannot carve a masked maze, and carves levels with
+/// [carve_by_level].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sidewinder {
+    bias: Direction,
+}
+
+impl Sidewinder {
+    /// Creates a new Sidewinder whose runs each carve a passage in the given direction.
+    pub fn new(bias: Direction) -> Sidewinder {
+        assert!(
+            Direction::ALL.contains(&bias),
+            "{bias:?} is not a compass direction"
+        );
+        Sidewinder { bias }
+    }
+
+    /// Returns the Sidewinder's bias direction.
+    pub fn bias(&self) -> Direction {
+        self.bias
+    }
+}
+
+/// Biased towards the north.
+impl Default for Sidewinder {
+    fn default() -> Sidewinder {
+        Sidewinder::new(Direction::North)
+    }
+}
+
+impl MazeAlgorithm for Sidewinder {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        assert!(
+            maze.mask().is_none(),
+            "Sidewinder cannot carve a masked maze"
+        );
+        if maze.levels() > 1 {
+            carve_by_level(self, maze, rng);
+            return;
+        }
+        // Runs are carved across the bias direction, in the direction of increasing coordinates.
+        let (across, lines, line_length) = match self.bias {
+            Direction::North | Direction::South => (Direction::East, maze.rows(), maze.columns()),
+            _ => (Direction::North, maze.columns(), maze.rows()),
+        };
+        let coordinates_at = |line: i32, position: i32| match across {
+            Direction::East => Coordinates::new(position, line),
+            _ => Coordinates::new(line, position),
+        };
+
+        let mut run: Vec<Coordinates> = Vec::new();
+        for line in 0..lines as i32 {
+            for position in 0..line_length as i32 {
+                let coordinates = coordinates_at(line, position);
+                run.push(coordinates);
+                let at_end = position + 1 == line_length as i32;
+                let at_bias_side = !maze.in_bounds(&(coordinates + self.bias.coordinates()));
+                if at_end || (!at_bias_side && rng.gen_bool(0.5)) {
+                    if !at_bias_side {
+                        let exit = run[rng.gen_range(0..run.len() as u32) as usize];
+                        maze.set_edge(&exit, &self.bias, CellEdge::Passage);
+                    }
+                    run.clear();
+                } else {
+                    maze.set_edge(&coordinates, &across, CellEdge::Passage);
+                }
+            }
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn binary_tree_carves_perfect_maze_for_every_corner() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        for vertical in [Direction::North, Direction::South] {
+            for horizontal in [Direction::East, Direction::West] {
+                let mut maze = Maze::new(13, 8);
+                BinaryTree::new(vertical, horizontal).carve(&mut maze, &mut rng);
+                test_support::assert_perfect(&maze);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_tree_bias_sides_are_corridors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let mut maze = Maze::new(13, 8);
+        BinaryTree::new(Direction::South, Direction::West).carve(&mut maze, &mut rng);
+        for column in 1..13 {
+            let cell = maze.cell(&Coordinates::new(column, 0)).unwrap();
+            assert_eq!(cell.edge(&Direction::West), Some(CellEdge::Passage));
+        }
+        for row in 1..8 {
+            let cell = maze.cell(&Coordinates::new(0, row)).unwrap();
+            assert_eq!(cell.edge(&Direction::South), Some(CellEdge::Passage));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "do not make a corner")]
+    fn binary_tree_rejects_opposite_directions() {
+        BinaryTree::new(Direction::North, Direction::South);
+    }
+
+    #[test]
+    fn sidewinder_carves_perfect_maze_for_every_bias() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        for bias in Direction::ALL {
+            let mut maze = Maze::new(11, 9);
+            Sidewinder::new(bias).carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn sidewinder_bias_side_is_corridor() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let mut maze = Maze::new(11, 9);
+        Sidewinder::new(Direction::East).carve(&mut maze, &mut rng);
+        for row in 1..9 {
+            let cell = maze.cell(&Coordinates::new(10, row)).unwrap();
+            assert_eq!(cell.edge(&Direction::South), Some(CellEdge::Passage));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+
+/// Removes the given fraction (between 0.0 and 1.0) of the maze's dead ends by knocking out
+/// a wall in each, which turns a perfect maze into one with loops and multiple routes.
+///
+/// A dead end's wall is knocked out towards a neighbor that is also a dead end if there is
+/// one, so that a single passage removes two dead ends. Dead ends with no interior walls,
+/// such as the ends of a maze that is only one cell wide, are left alone.
+pub fn braid<R: Rng + ?Sized>(maze: &mut Maze, fraction: f64, rng: &mut R) {
+    assert!(
+        (0.0..=1.0).contains(&fraction),
+        "Fraction {fraction} is not between 0 and 1"
+    );
+    let mut dead_ends = dead_ends(maze);
+    let target = (dead_ends.len() as f64 * fraction).round() as usize;
+    dead_ends.shuffle(rng);
+
+    let mut removed = 0;
+    for coordinates in dead_ends {
+        if removed >= target {
+            break;
+        }
+        if !is_dead_end(maze, &coordinates) {
+            // A neighbor's braiding has already removed it.
+            continue;
+        }
+        let walls: Vec<Direction> = maze
+            .directions()
+            .iter()
+            .copied()
+            .filter(|direction| {
+                maze.cell(&coordinates).unwrap().edge(direction) == Some(CellEdge::Wall)
+            })
+            .collect();
+        let dead_end_walls: Vec<Direction> = walls
+            .iter()
+            .copied()
+            .filter(|direction| is_dead_end(maze, &(coordinates + direction.coordinates())))
+            .collect();
+        let candidates = if dead_end_walls.is_empty() {
+            walls
+        } else {
+            dead_end_walls
+        };
+        if let Some(direction) = candidates.choose(rng) {
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            removed += if is_dead_end(maze, &neighbor_coordinates) {
+                2
+            } else {
+                1
+            };
+            maze.set_edge(&coordinates, direction, CellEdge::Passage);
+        }
+    }
+}
+
+/// Returns the coordinates of every dead end in the maze.
+pub fn dead_ends(maze: &Maze) -> Vec<Coordinates> {
+    maze.into_iter()
+        .flatten()
+        .filter(|cell| cell.is_dead_end())
+        .map(|cell| cell.coordinates())
+        .collect()
+}
+
+fn is_dead_end(maze: &Maze, coordinates: &Coordinates) -> bool {
+    maze.cell(coordinates)
+        .map(|cell| cell.is_dead_end())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use crate::maze_generator::{generate_with_seed, Kruskal, MazeAlgorithm};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn full_braid_removes_every_dead_end() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut maze = generate_with_seed(15, 10, 1);
+        braid(&mut maze, 1.0, &mut rng);
+        test_support::assert_consistent(&maze);
+        assert!(dead_ends(&maze).is_empty());
+    }
+
+    #[test]
+    fn zero_braid_leaves_maze_unchanged() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut maze = generate_with_seed(15, 10, 1);
+        braid(&mut maze, 0.0, &mut rng);
+        assert_eq!(maze, generate_with_seed(15, 10, 1));
+    }
+
+    #[test]
+    fn partial_braid_removes_about_that_fraction() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut maze = Maze::new(20, 20);
+        Kruskal.carve(&mut maze, &mut rng);
+        let before = dead_ends(&maze).len();
+        braid(&mut maze, 0.5, &mut rng);
+        let after = dead_ends(&maze).len();
+        let removed = before - after;
+        assert!(
+            removed >= before / 2 && removed <= before / 2 + 2,
+            "Removed {removed} of {before}"
+        );
+    }
+
+    #[test]
+    fn braid_keeps_every_cell_reachable_and_adds_loops() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut maze = generate_with_seed(12, 12, 3);
+        braid(&mut maze, 0.5, &mut rng);
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            144
+        );
+        assert!(test_support::passage_count(&maze) > 143);
+    }
+}
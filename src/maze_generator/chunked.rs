@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::cell::Cell;
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::disjoint_set::DisjointSet;
+use crate::maze_generator::{MazeAlgorithm, RecursiveBacktracker};
+
+/// Represents an unbounded maze, made of square chunks that are generated when they are first
+/// needed and then cached. Every chunk is generated from the world seed and its own chunk
+/// coordinates, so the same seed always produces the same maze, whatever order the chunks are
+/// generated in.
+///
+/// Each chunk is carved as a perfect maze with the [RecursiveBacktracker]. The chunks are
+/// grouped into square regions, and the chunks in each region are joined by one passage
+/// across each seam on a random spanning tree of the region's chunks, so each region is a
+/// perfect maze. Each region is then joined to its west and south neighbors by one passage
+/// each. The path between two neighboring cells never leaves the regions they are in, so it
+/// is never longer than two regions' worth of cells, at the cost of loops between regions. There
+/// are no borders: every edge is a wall or a passage.
+///
+/// The world stops short of the limits of `i32`: a cell whose region doesn't fit entirely
+/// within them can't be generated. The world is flat, with only level 0.
+///
+/// The caches are behind locks, so a ChunkedMaze can be shared between threads. Two
+/// ChunkedMazes are equal if they generate the same world, whatever they have cached.
+#[derive(Debug)]
+pub struct ChunkedMaze {
+    seed: u64,
+    chunk_size: u32,
+    region_size: u32,
+    chunks: Mutex<HashMap<(i32, i32), Vec<Cell>>>,
+    regions: Mutex<HashMap<(i32, i32), RegionLinks>>,
+}
+
+/// The seams that are open within a region, by the local chunk coordinates of the chunk on
+/// their north or east side and their direction, West or South, from it. Also the local row
+/// of the chunk joined to the region to the west, and the local column of the chunk joined to
+/// the region to the south.
+#[derive(Clone, Debug)]
+struct RegionLinks {
+    seams: Vec<((i32, i32), Direction)>,
+    west_link: i32,
+    south_link: i32,
+}
+
+/// Associated functions to create and use a ChunkedMaze struct.
+impl ChunkedMaze {
+    /// Creates a new ChunkedMaze with the given world seed and chunks of the given width and
+    /// height, grouped into regions of 4 by 4 chunks. No chunks are generated until they are
+    /// needed.
+    pub fn new(seed: u64, chunk_size: u32) -> ChunkedMaze {
+        assert!(chunk_size > 0, "Chunk size must be at least 1");
+        assert!(
+            chunk_size <= i32::MAX as u32,
+            "Chunk size {chunk_size} is too large"
+        );
+        ChunkedMaze {
+            seed,
+            chunk_size,
+            region_size: 4,
+            chunks: Mutex::new(HashMap::new()),
+            regions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the ChunkedMaze with its chunks grouped into regions of the given number of
+    /// chunks across and up. Larger regions have fewer loops, and longer paths between
+    /// neighboring cells. Any chunks that were already generated are discarded.
+    pub fn with_region_size(mut self, region_size: u32) -> ChunkedMaze {
+        assert!(region_size > 0, "Region size must be at least 1");
+        assert!(
+            region_size <= i32::MAX as u32,
+            "Region size {region_size} is too large"
+        );
+        self.region_size = region_size;
+        self.chunks.get_mut().unwrap().clear();
+        self.regions.get_mut().unwrap().clear();
+        self
+    }
+
+    /// Returns the world seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the width and height of a chunk.
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    /// Returns the width and height of a region, in chunks.
+    pub fn region_size(&self) -> u32 {
+        self.region_size
+    }
+
+    /// Returns the number of chunks that have been generated and cached.
+    pub fn cached_chunks(&self) -> usize {
+        self.chunks.lock().unwrap().len()
+    }
+
+    /// Returns the chunk coordinates of the chunk containing the cell at the given coordinates.
+    pub fn chunk_of(&self, coordinates: &Coordinates) -> (i32, i32) {
+        let size = self.chunk_size as i32;
+        (
+            coordinates.column().div_euclid(size),
+            coordinates.row().div_euclid(size),
+        )
+    }
+
+    /// Returns the region coordinates of the region containing the chunk with the given chunk
+    /// coordinates.
+    pub fn region_of(&self, (chunk_column, chunk_row): (i32, i32)) -> (i32, i32) {
+        let size = self.region_size as i32;
+        (chunk_column.div_euclid(size), chunk_row.div_euclid(size))
+    }
+
+    /// Returns the cell at the given coordinates, generating its chunk if it hasn't been
+    /// generated yet.
+    ///
+    /// Panics if the coordinates are not on level 0, or if the cell's region doesn't fit
+    /// within the limits of `i32`.
+    pub fn cell(&self, coordinates: &Coordinates) -> Cell {
+        assert_eq!(
+            coordinates.level(),
+            0,
+            "{coordinates:?} is not on level 0 of the world"
+        );
+        let chunk = self.chunk_of(coordinates);
+        assert!(
+            self.region_fits(self.region_of(chunk)),
+            "{coordinates:?} is too close to the edge of the world"
+        );
+        let size = self.chunk_size as i32;
+        let index =
+            coordinates.row().rem_euclid(size) * size + coordinates.column().rem_euclid(size);
+        if let Some(cells) = self.chunks.lock().unwrap().get(&chunk) {
+            return cells[index as usize];
+        }
+        // The lock isn't held while generating, so other threads can read other chunks.
+        let cells = self.generate_chunk(chunk);
+        self.chunks.lock().unwrap().entry(chunk).or_insert(cells)[index as usize]
+    }
+
+    /// Discards the cached chunk with the given chunk coordinates, if there is one. It will be
+    /// generated again, identically, if it is needed. Once none of a region's chunks are
+    /// cached, the region's open seams are discarded too.
+    pub fn forget_chunk(&mut self, chunk: (i32, i32)) {
+        let region = self.region_of(chunk);
+        let chunks = self.chunks.get_mut().unwrap();
+        chunks.remove(&chunk);
+        let size = self.region_size as i32;
+        let in_region =
+            |(column, row): &(i32, i32)| (column.div_euclid(size), row.div_euclid(size)) == region;
+        if !chunks.keys().any(in_region) {
+            self.regions.get_mut().unwrap().remove(&region);
+        }
+    }
+
+    /// Returns true if every cell of the region with the given region coordinates has
+    /// coordinates within the limits of `i32`.
+    fn region_fits(&self, region: (i32, i32)) -> bool {
+        let span = self.region_size as i64 * self.chunk_size as i64;
+        [region.0, region.1].into_iter().all(|region| {
+            (region as i64)
+                .checked_mul(span)
+                .and_then(|start| Some((start, start.checked_add(span - 1)?)))
+                .is_some_and(|(start, end)| start >= i32::MIN as i64 && end <= i32::MAX as i64)
+        })
+    }
+
+    /// Returns the cells of the chunk with the given chunk coordinates, in row order.
+    fn generate_chunk(&self, (chunk_column, chunk_row): (i32, i32)) -> Vec<Cell> {
+        let size = self.chunk_size as i32;
+        let mut maze = Maze::new(self.chunk_size, self.chunk_size);
+        let mut rng = ChaCha8Rng::seed_from_u64(chunk_seed(self.seed, chunk_column, chunk_row));
+        RecursiveBacktracker.carve(&mut maze, &mut rng);
+
+        // The seam passages to this chunk's west and south neighbors, and those of its east
+        // and north neighbors that lead into it.
+        let mut seams = Vec::new();
+        if let Some(offset) = self.seam((chunk_column, chunk_row), Direction::West) {
+            seams.push((Coordinates::new(0, offset), Direction::West));
+        }
+        if let Some(offset) = self.seam((chunk_column, chunk_row), Direction::South) {
+            seams.push((Coordinates::new(offset, 0), Direction::South));
+        }
+        if let Some(east) = chunk_column.checked_add(1) {
+            if let Some(offset) = self.seam((east, chunk_row), Direction::West) {
+                seams.push((Coordinates::new(size - 1, offset), Direction::East));
+            }
+        }
+        if let Some(north) = chunk_row.checked_add(1) {
+            if let Some(offset) = self.seam((chunk_column, north), Direction::South) {
+                seams.push((Coordinates::new(offset, size - 1), Direction::North));
+            }
+        }
+
+        let origin = Coordinates::new(chunk_column * size, chunk_row * size);
+        maze.into_iter()
+            .flatten()
+            .map(|local_cell| {
+                let mut cell = Cell::new(local_cell.coordinates() + origin);
+                for direction in Direction::ALL {
+                    let edge = if seams.contains(&(local_cell.coordinates(), direction)) {
+                        Some(CellEdge::Passage)
+                    } else if local_cell.edge(&direction) == Some(CellEdge::Border) {
+                        Some(CellEdge::Wall)
+                    } else {
+                        local_cell.edge(&direction)
+                    };
+                    cell.set_edge(&direction, edge);
+                }
+                cell
+            })
+            .collect()
+    }
+
+    /// Returns the offset along the seam of the passage between the chunk with the given
+    /// chunk coordinates and its neighbor in the given direction, West or South, or None if
+    /// the seam is closed.
+    fn seam(&self, chunk: (i32, i32), direction: Direction) -> Option<i32> {
+        let neighbor = match direction {
+            Direction::West => (chunk.0.checked_sub(1)?, chunk.1),
+            _ => (chunk.0, chunk.1.checked_sub(1)?),
+        };
+        let region = self.region_of(chunk);
+        if !self.region_fits(region) || !self.region_fits(self.region_of(neighbor)) {
+            return None;
+        }
+        let size = self.region_size as i32;
+        let local = (chunk.0.rem_euclid(size), chunk.1.rem_euclid(size));
+        let mut regions = self.regions.lock().unwrap();
+        let links = regions
+            .entry(region)
+            .or_insert_with(|| self.region_links(region));
+        let is_open = if self.region_of(neighbor) == region {
+            links.seams.contains(&(local, direction))
+        } else if direction == Direction::West {
+            links.west_link == local.1
+        } else {
+            links.south_link == local.0
+        };
+        drop(regions);
+        if !is_open {
+            return None;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(chunk_seed(self.seed, chunk.0, chunk.1));
+        // The chunk's carving uses stream 0, so its seams don't depend on how it is carved.
+        rng.set_stream(1);
+        let west_offset = rng.gen_range(0..self.chunk_size) as i32;
+        let south_offset = rng.gen_range(0..self.chunk_size) as i32;
+        Some(match direction {
+            Direction::West => west_offset,
+            _ => south_offset,
+        })
+    }
+
+    /// Returns the open seams of the region with the given region coordinates: a random
+    /// spanning tree of its chunks, found with Kruskal's algorithm, and the chunks joined to
+    /// its west and south neighbors.
+    fn region_links(&self, (region_column, region_row): (i32, i32)) -> RegionLinks {
+        let size = self.region_size as i32;
+        let mut rng = ChaCha8Rng::seed_from_u64(chunk_seed(self.seed, region_column, region_row));
+        // Streams 0 and 1 are used by the chunk with the same coordinates.
+        rng.set_stream(2);
+
+        let mut candidates = Vec::new();
+        for row in 0..size {
+            for column in 0..size {
+                if column > 0 {
+                    candidates.push(((column, row), Direction::West));
+                }
+                if row > 0 {
+                    candidates.push(((column, row), Direction::South));
+                }
+            }
+        }
+        candidates.shuffle(&mut rng);
+
+        let width = size as usize;
+        let mut sets = DisjointSet::new(width * width);
+        let seams = candidates
+            .into_iter()
+            .filter(|((column, row), direction)| {
+                let index = *row as usize * width + *column as usize;
+                let neighbor_index = match direction {
+                    Direction::West => index - 1,
+                    _ => index - width,
+                };
+                sets.union(index, neighbor_index)
+            })
+            .collect();
+        RegionLinks {
+            seams,
+            west_link: rng.gen_range(0..size),
+            south_link: rng.gen_range(0..size),
+        }
+    }
+}
+
+impl Clone for ChunkedMaze {
+    fn clone(&self) -> ChunkedMaze {
+        ChunkedMaze {
+            seed: self.seed,
+            chunk_size: self.chunk_size,
+            region_size: self.region_size,
+            chunks: Mutex::new(self.chunks.lock().unwrap().clone()),
+            regions: Mutex::new(self.regions.lock().unwrap().clone()),
+        }
+    }
+}
+
+impl PartialEq for ChunkedMaze {
+    fn eq(&self, other: &ChunkedMaze) -> bool {
+        self.seed == other.seed
+            && self.chunk_size == other.chunk_size
+            && self.region_size == other.region_size
+    }
+}
+
+/// Returns a seed for the chunk with the given chunk coordinates, mixed from the world seed
+/// with the SplitMix64 finalizer so that neighboring chunks get unrelated seeds.
+fn chunk_seed(seed: u64, chunk_column: i32, chunk_row: i32) -> u64 {
+    let mut hash = seed;
+    for value in [chunk_column as u32 as u64, chunk_row as u32 as u64] {
+        hash = (hash ^ value).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the coordinates of every cell in the square of chunks from the given chunk
+    /// coordinates to their negatives.
+    fn window(maze: &ChunkedMaze, chunks: i32) -> Vec<Coordinates> {
+        let size = maze.chunk_size() as i32;
+        let mut coordinates = Vec::new();
+        for row in -chunks * size..(chunks + 1) * size {
+            for column in -chunks * size..(chunks + 1) * size {
+                coordinates.push(Coordinates::new(column, row));
+            }
+        }
+        coordinates
+    }
+
+    /// Returns the region coordinates of the region containing the cell at the given
+    /// coordinates.
+    fn region_of_cell(maze: &ChunkedMaze, coordinates: &Coordinates) -> (i32, i32) {
+        maze.region_of(maze.chunk_of(coordinates))
+    }
+
+    /// Returns the length of the shortest path between the cells at the given coordinates
+    /// that only passes through cells in the given regions, if there is one.
+    fn path_length_within(
+        maze: &ChunkedMaze,
+        from: Coordinates,
+        to: Coordinates,
+        regions: &[(i32, i32)],
+    ) -> Option<usize> {
+        let key = |coordinates: &Coordinates| (coordinates.column(), coordinates.row());
+        let mut distances = HashMap::from([(key(&from), 0)]);
+        let mut frontier = std::collections::VecDeque::from([from]);
+        while let Some(coordinates) = frontier.pop_front() {
+            let distance = distances[&key(&coordinates)];
+            if coordinates == to {
+                return Some(distance);
+            }
+            let cell = maze.cell(&coordinates);
+            for direction in Direction::ALL {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if cell.edge(&direction) == Some(CellEdge::Passage)
+                    && regions.contains(&region_of_cell(maze, &neighbor_coordinates))
+                    && !distances.contains_key(&key(&neighbor_coordinates))
+                {
+                    distances.insert(key(&neighbor_coordinates), distance + 1);
+                    frontier.push_back(neighbor_coordinates);
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn edges_agree_across_seams() {
+        let maze = ChunkedMaze::new(22, 5);
+        for coordinates in window(&maze, 2) {
+            let cell = maze.cell(&coordinates);
+            assert_eq!(cell.coordinates(), coordinates);
+            for direction in Direction::ALL {
+                let edge = cell.edge(&direction);
+                assert!(matches!(edge, Some(CellEdge::Wall | CellEdge::Passage)));
+                let neighbor = maze.cell(&(coordinates + direction.coordinates()));
+                assert_eq!(
+                    neighbor.edge(&direction.opposite()),
+                    edge,
+                    "{direction:?} of {coordinates:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn regions_are_perfect() {
+        let maze = ChunkedMaze::new(22, 4).with_region_size(3);
+        let span = 12;
+        for (region_column, region_row) in [(0, 0), (-1, -1), (2, -3)] {
+            let origin = Coordinates::new(region_column * span, region_row * span);
+            let index = |coordinates: &Coordinates| {
+                ((coordinates.row() - origin.row()) * span + coordinates.column() - origin.column())
+                    as usize
+            };
+            let mut sets = DisjointSet::new((span * span) as usize);
+            let mut passages = 0;
+            for row in 0..span {
+                for column in 0..span {
+                    let coordinates = origin + Coordinates::new(column, row);
+                    let cell = maze.cell(&coordinates);
+                    for direction in [Direction::North, Direction::East] {
+                        let neighbor_coordinates = coordinates + direction.coordinates();
+                        if cell.edge(&direction) == Some(CellEdge::Passage)
+                            && region_of_cell(&maze, &neighbor_coordinates)
+                                == (region_column, region_row)
+                        {
+                            assert!(sets.union(index(&coordinates), index(&neighbor_coordinates)));
+                            passages += 1;
+                        }
+                    }
+                }
+            }
+            assert_eq!(passages, span * span - 1);
+        }
+    }
+
+    #[test]
+    fn regions_are_joined_by_one_passage() {
+        let maze = ChunkedMaze::new(22, 4).with_region_size(3);
+        let crossings = |column: i32, direction: Direction| {
+            (0..12)
+                .filter(|offset| {
+                    let coordinates = match direction {
+                        Direction::West => Coordinates::new(column, *offset),
+                        _ => Coordinates::new(*offset, column),
+                    };
+                    maze.cell(&coordinates).edge(&direction) == Some(CellEdge::Passage)
+                })
+                .count()
+        };
+        assert_eq!(crossings(0, Direction::West), 1);
+        assert_eq!(crossings(0, Direction::South), 1);
+        assert_eq!(crossings(12, Direction::West), 1);
+    }
+
+    #[test]
+    fn neighbors_are_joined_within_their_regions() {
+        let maze = ChunkedMaze::new(7, 3).with_region_size(2);
+        for coordinates in window(&maze, 3) {
+            for direction in [Direction::North, Direction::East] {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                let regions = [
+                    region_of_cell(&maze, &coordinates),
+                    region_of_cell(&maze, &neighbor_coordinates),
+                ];
+                let length = path_length_within(&maze, coordinates, neighbor_coordinates, &regions);
+                assert!(
+                    length.is_some_and(|length| length < 2 * 6 * 6),
+                    "{coordinates:?} to {neighbor_coordinates:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_cells_in_any_order() {
+        let forwards = ChunkedMaze::new(7, 6);
+        let backwards = ChunkedMaze::new(7, 6);
+        let coordinates = window(&forwards, 2);
+        let cells: Vec<Cell> = coordinates.iter().map(|c| forwards.cell(c)).collect();
+        let reversed: Vec<Cell> = coordinates
+            .iter()
+            .rev()
+            .map(|c| backwards.cell(c))
+            .collect();
+        assert!(cells.iter().eq(reversed.iter().rev()));
+        let other = ChunkedMaze::new(8, 6);
+        let other_cells: Vec<Cell> = coordinates.iter().map(|c| other.cell(c)).collect();
+        assert_ne!(cells, other_cells);
+    }
+
+    #[test]
+    fn generates_chunks_lazily() {
+        let mut maze = ChunkedMaze::new(22, 5);
+        assert_eq!(maze.cached_chunks(), 0);
+        let first = maze.cell(&Coordinates::new(-1, -1));
+        maze.cell(&Coordinates::new(-5, -5));
+        assert_eq!(maze.cached_chunks(), 1);
+        assert_eq!(maze.chunk_of(&Coordinates::new(-1, -1)), (-1, -1));
+        maze.forget_chunk((-1, -1));
+        assert_eq!(maze.cached_chunks(), 0);
+        assert_eq!(maze.cell(&Coordinates::new(-1, -1)), first);
+    }
+
+    #[test]
+    fn equality_ignores_cached_chunks() {
+        let maze = ChunkedMaze::new(22, 5);
+        let other = maze.clone();
+        maze.cell(&Coordinates::new(3, 3));
+        assert_eq!(maze, other);
+        assert_ne!(maze, ChunkedMaze::new(22, 5).with_region_size(2));
+    }
+
+    #[test]
+    fn shares_chunks_between_threads() {
+        let maze = ChunkedMaze::new(22, 5);
+        let coordinates = window(&maze, 1);
+        let cells: Vec<Vec<Cell>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| coordinates.iter().map(|c| maze.cell(c)).collect()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        assert!(cells.windows(2).all(|pair| pair[0] == pair[1]));
+        assert_eq!(maze.cached_chunks(), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not on level 0")]
+    fn rejects_cells_on_other_levels() {
+        ChunkedMaze::new(22, 5).cell(&Coordinates::with_level(0, 0, 1));
+    }
+
+    #[test]
+    fn generates_cells_at_the_limits() {
+        // Regions of 8 cells fit exactly between the limits of i32.
+        let maze = ChunkedMaze::new(22, 4).with_region_size(2);
+        let corner = maze.cell(&Coordinates::new(i32::MAX, i32::MAX));
+        assert_eq!(corner.edge(&Direction::East), Some(CellEdge::Wall));
+        assert_eq!(corner.edge(&Direction::North), Some(CellEdge::Wall));
+        let corner = maze.cell(&Coordinates::new(i32::MIN, i32::MIN));
+        assert_eq!(corner.edge(&Direction::West), Some(CellEdge::Wall));
+        assert_eq!(corner.edge(&Direction::South), Some(CellEdge::Wall));
+    }
+
+    #[test]
+    fn joins_cells_next_to_regions_past_the_limits() {
+        // Regions of 6 cells don't divide the range of i32, so the last one is cut short, and
+        // the seam into it from the last cell of the region before it is closed.
+        let maze = ChunkedMaze::new(22, 3).with_region_size(2);
+        let inside = Coordinates::new(i32::MAX - 2, 0);
+        assert_eq!(
+            maze.cell(&inside).edge(&Direction::East),
+            Some(CellEdge::Wall)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "too close to the edge of the world")]
+    fn rejects_cells_in_regions_past_the_limits() {
+        let maze = ChunkedMaze::new(22, 3).with_region_size(2);
+        maze.cell(&Coordinates::new(i32::MAX, 0));
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+
+use rand::RngCore;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{Kruskal, MazeAlgorithm};
+use crate::maze_solver;
+
+/// Represents an open rectangular room on one level of a maze, whose cells are all joined by
+/// passages.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Room {
+    column: i32,
+    row: i32,
+    level: i32,
+    width: u32,
+    height: u32,
+}
+
+/// Associated functions to create and use a Room struct.
+impl Room {
+    /// Creates a new Room with its south-west corner at the given coordinates, on their level.
+    pub fn new(coordinates: Coordinates, width: u32, height: u32) -> Room {
+        Room {
+            column: coordinates.column(),
+            row: coordinates.row(),
+            level: coordinates.level(),
+            width,
+            height,
+        }
+    }
+
+    /// Returns the coordinates of the Room's south-west corner.
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates::with_level(self.column, self.row, self.level)
+    }
+
+    /// Returns the Room's width in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the Room's height in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns true if the cell at the given coordinates is inside the Room.
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        coordinates.level() == self.level
+            && (self.column..self.column + self.width as i32).contains(&coordinates.column())
+            && (self.row..self.row + self.height as i32).contains(&coordinates.row())
+    }
+
+    /// Returns the Room's interior edges, each listed once from the cell on its south or
+    /// west side.
+    pub(crate) fn interior_edges(&self) -> Vec<(Coordinates, Direction)> {
+        let mut edges = Vec::new();
+        for row in self.row..self.row + self.height as i32 {
+            for column in self.column..self.column + self.width as i32 {
+                let coordinates = Coordinates::with_level(column, row, self.level);
+                for direction in [Direction::North, Direction::East] {
+                    if self.contains(&(coordinates + direction.coordinates())) {
+                        edges.push((coordinates, direction));
+                    }
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// Enumerates the ways a set of [Constraints] can be impossible to satisfy.
+#[derive(Debug, PartialEq)]
+pub enum ConstraintError {
+    /// A pinned edge, or a room, does not lie between two active cells of the maze.
+    OutOfBounds(Coordinates, Direction),
+    /// The same edge is pinned as both a passage and a wall.
+    Conflict(Coordinates, Direction),
+    /// The pinned walls cut some cells off from the rest of the maze.
+    Unreachable,
+    /// The maze has no active cells to carve.
+    Empty,
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::OutOfBounds(coordinates, direction) => write!(
+                f,
+                "edge {direction:?} of {coordinates:?} is not between two cells of the maze"
+            ),
+            ConstraintError::Conflict(coordinates, direction) => write!(
+                f,
+                "edge {direction:?} of {coordinates:?} is pinned as both a passage and a wall"
+            ),
+            ConstraintError::Unreachable => {
+                write!(
+                    f,
+                    "pinned walls cut some cells off from the rest of the maze"
+                )
+            }
+            ConstraintError::Empty => write!(f, "the maze has no active cells"),
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// Represents passages, walls and rooms that are fixed in place before a maze is generated.
+/// The rest of the maze is carved around them, so that every cell can still be reached.
+///
+/// The rest of the maze is always carved with [Kruskal]'s algorithm, which is the only one
+/// that joins up cells that were populated before it started. The other algorithms treat a
+/// pinned cell as already visited, and would wall it off.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Constraints {
+    pins: Vec<(Coordinates, Direction, CellEdge)>,
+    rooms: Vec<Room>,
+}
+
+/// Associated functions to create and use a Constraints struct.
+impl Constraints {
+    /// Creates a new Constraints struct with nothing pinned.
+    pub fn new() -> Constraints {
+        Constraints::default()
+    }
+
+    /// Returns the Constraints with a passage pinned in the given direction from the cell at
+    /// the given coordinates.
+    pub fn with_passage(mut self, coordinates: Coordinates, direction: Direction) -> Constraints {
+        self.pins.push((coordinates, direction, CellEdge::Passage));
+        self
+    }
+
+    /// Returns the Constraints with a wall pinned in the given direction from the cell at the
+    /// given coordinates.
+    pub fn with_wall(mut self, coordinates: Coordinates, direction: Direction) -> Constraints {
+        self.pins.push((coordinates, direction, CellEdge::Wall));
+        self
+    }
+
+    /// Returns the Constraints with the given open room. The room's walls are carved by the
+    /// generator like any others, so it ends up with at least one doorway.
+    pub fn with_room(mut self, room: Room) -> Constraints {
+        self.rooms.push(room);
+        self
+    }
+
+    /// Returns the Constraints' rooms.
+    pub fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    /// Carves the given unpopulated maze around the pinned passages, walls and rooms, with
+    /// [Kruskal]'s algorithm.
+    ///
+    /// Returns an error, leaving the maze as it was, if a pin or room is out of bounds, if an
+    /// edge is pinned as both a passage and a wall, if the pinned walls make some cells
+    /// unreachable, or if the maze has no active cells.
+    pub fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), ConstraintError> {
+        let mut carved = maze.clone();
+        self.carve_into(&mut carved, rng)?;
+        *maze = carved;
+        Ok(())
+    }
+
+    fn carve_into(&self, maze: &mut Maze, rng: &mut dyn RngCore) -> Result<(), ConstraintError> {
+        let room_pins = self.rooms.iter().flat_map(|room| {
+            room.interior_edges()
+                .into_iter()
+                .map(|(coordinates, direction)| (coordinates, direction, CellEdge::Passage))
+        });
+        for (coordinates, direction, cell_edge) in self.pins.iter().copied().chain(room_pins) {
+            if !maze.is_active(&coordinates)
+                || !maze.is_active(&(coordinates + direction.coordinates()))
+            {
+                return Err(ConstraintError::OutOfBounds(coordinates, direction));
+            }
+            let existing = maze
+                .cell(&coordinates)
+                .and_then(|cell| cell.edge(&direction));
+            if existing.is_some() && existing != Some(cell_edge) {
+                return Err(ConstraintError::Conflict(coordinates, direction));
+            }
+            maze.set_edge(&coordinates, &direction, cell_edge);
+        }
+
+        Kruskal.carve(maze, rng);
+
+        let start = match maze.into_iter().flatten().next() {
+            Some(cell) => cell.coordinates(),
+            None => return Err(ConstraintError::Empty),
+        };
+        let distances = maze_solver::distances(maze, &start);
+        let reachable = maze
+            .into_iter()
+            .flatten()
+            .filter(|cell| distances.distance(&cell.coordinates()).is_some())
+            .count();
+        if reachable == maze.active_count() {
+            Ok(())
+        } else {
+            Err(ConstraintError::Unreachable)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn keeps_pinned_passages_and_walls() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let constraints = Constraints::new()
+            .with_passage(Coordinates::new(0, 0), Direction::East)
+            .with_passage(Coordinates::new(1, 0), Direction::East)
+            .with_wall(Coordinates::new(3, 3), Direction::North);
+        let mut maze = Maze::new(8, 8);
+        constraints.carve(&mut maze, &mut rng).unwrap();
+        test_support::assert_perfect(&maze);
+        let edge = |column, row, direction| {
+            maze.cell(&Coordinates::new(column, row))
+                .unwrap()
+                .edge(&direction)
+        };
+        assert_eq!(edge(0, 0, Direction::East), Some(CellEdge::Passage));
+        assert_eq!(edge(1, 0, Direction::East), Some(CellEdge::Passage));
+        assert_eq!(edge(3, 3, Direction::North), Some(CellEdge::Wall));
+    }
+
+    #[test]
+    fn carves_open_room_with_doorway() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let room = Room::new(Coordinates::new(3, 2), 4, 3);
+        let mut maze = Maze::new(10, 8);
+        Constraints::new()
+            .with_room(room)
+            .carve(&mut maze, &mut rng)
+            .unwrap();
+        test_support::assert_consistent(&maze);
+        for (coordinates, direction) in room.interior_edges() {
+            let cell = maze.cell(&coordinates).unwrap();
+            assert_eq!(cell.edge(&direction), Some(CellEdge::Passage));
+        }
+        // The room's loops are the only ones, and it is joined to the rest by one doorway.
+        let room_loops = room.interior_edges().len() - (4 * 3 - 1);
+        assert_eq!(test_support::passage_count(&maze), 80 - 1 + room_loops);
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            80
+        );
+    }
+
+    #[test]
+    fn carves_room_on_upper_level() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let room = Room::new(Coordinates::with_level(1, 1, 1), 3, 2);
+        let mut maze = Maze::with_levels(5, 4, 2);
+        Constraints::new()
+            .with_room(room)
+            .carve(&mut maze, &mut rng)
+            .unwrap();
+        assert!(!room.contains(&Coordinates::new(1, 1)));
+        for (coordinates, direction) in room.interior_edges() {
+            assert_eq!(coordinates.level(), 1);
+            let cell = maze.cell(&coordinates).unwrap();
+            assert_eq!(cell.edge(&direction), Some(CellEdge::Passage));
+        }
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            40
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_pin() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut maze = Maze::new(4, 4);
+        let result = Constraints::new()
+            .with_passage(Coordinates::new(3, 0), Direction::East)
+            .carve(&mut maze, &mut rng);
+        assert_eq!(
+            result,
+            Err(ConstraintError::OutOfBounds(
+                Coordinates::new(3, 0),
+                Direction::East
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_pins() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut maze = Maze::new(4, 4);
+        let result = Constraints::new()
+            .with_wall(Coordinates::new(1, 1), Direction::North)
+            .with_passage(Coordinates::new(1, 2), Direction::South)
+            .carve(&mut maze, &mut rng);
+        assert_eq!(
+            result,
+            Err(ConstraintError::Conflict(
+                Coordinates::new(1, 2),
+                Direction::South
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_walls_that_cut_off_cells() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut maze = Maze::new(4, 4);
+        let result = Constraints::new()
+            .with_wall(Coordinates::new(0, 0), Direction::North)
+            .with_wall(Coordinates::new(0, 0), Direction::East)
+            .carve(&mut maze, &mut rng);
+        assert_eq!(result, Err(ConstraintError::Unreachable));
+        assert_eq!(maze, Maze::new(4, 4));
+    }
+
+    #[test]
+    fn leaves_maze_unchanged_on_error() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut maze = Maze::new(4, 4);
+        let result = Constraints::new()
+            .with_passage(Coordinates::new(0, 0), Direction::East)
+            .with_wall(Coordinates::new(2, 2), Direction::North)
+            .with_passage(Coordinates::new(3, 3), Direction::North)
+            .carve(&mut maze, &mut rng);
+        assert!(matches!(result, Err(ConstraintError::OutOfBounds(..))));
+        assert_eq!(maze, Maze::new(4, 4));
+    }
+
+    #[test]
+    fn rejects_maze_with_no_cells() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut maze = Maze::new(0, 0);
+        let result = Constraints::new().carve(&mut maze, &mut rng);
+        assert_eq!(result, Err(ConstraintError::Empty));
+    }
+}
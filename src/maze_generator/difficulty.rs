@@ -0,0 +1,407 @@
+use rand::Rng;
+
+use crate::maze::Maze;
+use crate::maze_generator::algorithm;
+use crate::maze_generator::braid::dead_ends;
+use crate::maze_generator::openings::{place_openings, OpeningStrategy};
+use crate::maze_solver;
+
+/// Measurements of how hard a maze with an entrance and an exit is to solve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metrics {
+    cells: usize,
+    solution_length: usize,
+    dead_ends: usize,
+    decision_points: usize,
+}
+
+/// Associated functions to use a Metrics struct.
+impl Metrics {
+    /// Measures the path from the maze's entrance to its exit, or returns None if it is
+    /// missing either of them or there is no path. See [maze_solver::solve_openings].
+    pub fn measure(maze: &Maze) -> Option<Metrics> {
+        let path = maze_solver::solve_openings(maze)?;
+        // A solver has a choice to make wherever there is more than one way onwards: any
+        // way out of the entrance cell, or any way out of a later cell but back.
+        let decision_points = path[..path.len() - 1]
+            .iter()
+            .enumerate()
+            .filter(|(index, coordinates)| {
+                let ways_on = maze_solver::moves(maze, coordinates).len() - index.min(&1);
+                ways_on > 1
+            })
+            .count();
+        Some(Metrics {
+            cells: maze.active_count(),
+            solution_length: path.len(),
+            dead_ends: dead_ends(maze).len(),
+            decision_points,
+        })
+    }
+
+    /// Returns the number of cells in the maze.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Returns the number of cells on the solution path, including the entrance and exit.
+    pub fn solution_length(&self) -> usize {
+        self.solution_length
+    }
+
+    /// Returns the number of dead ends in the maze.
+    pub fn dead_ends(&self) -> usize {
+        self.dead_ends
+    }
+
+    /// Returns the number of cells on the solution path where there is more than one way on.
+    pub fn decision_points(&self) -> usize {
+        self.decision_points
+    }
+
+    /// Returns the fraction of the maze's cells that are on the solution path.
+    pub fn solution_ratio(&self) -> f64 {
+        ratio(self.solution_length, self.cells)
+    }
+
+    /// Returns the fraction of the maze's cells that are dead ends.
+    pub fn dead_end_ratio(&self) -> f64 {
+        ratio(self.dead_ends, self.cells)
+    }
+
+    /// Returns the fraction of the solution path's cells that are decision points.
+    pub fn decision_ratio(&self) -> f64 {
+        ratio(self.decision_points, self.solution_length)
+    }
+}
+
+/// Returns the numerator as a fraction of the denominator, or 0.0 if the denominator is 0.
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// The algorithms and opening strategies that [Difficulty::generate] searches, which between
+/// them range from short, branchy solutions to long, winding ones.
+const CANDIDATE_ALGORITHMS: [&str; 12] = [
+    "recursive-backtracker",
+    "hunt-and-kill",
+    "growing-tree",
+    "kruskal",
+    "prim",
+    "simplified-prim",
+    "wilson",
+    "aldous-broder",
+    "eller",
+    "recursive-division",
+    "binary-tree",
+    "sidewinder",
+];
+const CANDIDATE_STRATEGIES: [OpeningStrategy; 3] = [
+    OpeningStrategy::OppositeSides,
+    OpeningStrategy::FarthestApart,
+    OpeningStrategy::Random,
+];
+
+/// A target for how hard a maze should be, given as the [Metrics] ratios to aim for, and how
+/// far from them the result may be. Ratios that aren't set are not targeted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Difficulty {
+    solution_ratio: Option<f64>,
+    dead_end_ratio: Option<f64>,
+    decision_ratio: Option<f64>,
+    tolerance: f64,
+    attempts: u32,
+}
+
+/// Associated functions to create and use a Difficulty struct.
+impl Difficulty {
+    /// Creates a new Difficulty that targets nothing, with a tolerance of 0.05 and 100
+    /// attempts.
+    pub fn new() -> Difficulty {
+        Difficulty {
+            solution_ratio: None,
+            dead_end_ratio: None,
+            decision_ratio: None,
+            tolerance: 0.05,
+            attempts: 100,
+        }
+    }
+
+    /// Creates a new Difficulty for easy mazes: few dead ends, and a solution with few
+    /// decisions along it. Like the other presets, its targets are fixed ratios tuned for
+    /// mazes of a few hundred cells, so they are harder to meet in much smaller or larger
+    /// mazes.
+    pub fn easy() -> Difficulty {
+        Difficulty::new()
+            .with_solution_ratio(0.15)
+            .with_dead_end_ratio(0.1)
+            .with_decision_ratio(0.2)
+    }
+
+    /// Creates a new Difficulty for medium mazes. Each of its targets is between the easy
+    /// and hard ones.
+    pub fn medium() -> Difficulty {
+        Difficulty::new()
+            .with_solution_ratio(0.3)
+            .with_dead_end_ratio(0.2)
+            .with_decision_ratio(0.3)
+    }
+
+    /// Creates a new Difficulty for hard mazes: many dead ends, and a long solution with
+    /// decisions at nearly half its cells.
+    pub fn hard() -> Difficulty {
+        Difficulty::new()
+            .with_solution_ratio(0.35)
+            .with_dead_end_ratio(0.3)
+            .with_decision_ratio(0.45)
+    }
+
+    /// Returns the Difficulty targeting the given fraction (between 0.0 and 1.0) of cells on
+    /// the solution path.
+    pub fn with_solution_ratio(mut self, solution_ratio: f64) -> Difficulty {
+        assert_ratio(solution_ratio);
+        self.solution_ratio = Some(solution_ratio);
+        self
+    }
+
+    /// Returns the Difficulty targeting the given fraction (between 0.0 and 1.0) of cells
+    /// that are dead ends.
+    pub fn with_dead_end_ratio(mut self, dead_end_ratio: f64) -> Difficulty {
+        assert_ratio(dead_end_ratio);
+        self.dead_end_ratio = Some(dead_end_ratio);
+        self
+    }
+
+    /// Returns the Difficulty targeting the given fraction (between 0.0 and 1.0) of the
+    /// solution path's cells that are decision points.
+    pub fn with_decision_ratio(mut self, decision_ratio: f64) -> Difficulty {
+        assert_ratio(decision_ratio);
+        self.decision_ratio = Some(decision_ratio);
+        self
+    }
+
+    /// Returns the Difficulty allowing each targeted ratio to be off by up to the given
+    /// amount.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Difficulty {
+        assert!(tolerance > 0.0, "Tolerance {tolerance} is not positive");
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the Difficulty generating up to the given number of mazes while searching.
+    pub fn with_attempts(mut self, attempts: u32) -> Difficulty {
+        assert!(attempts > 0, "Attempts must be at least 1");
+        self.attempts = attempts;
+        self
+    }
+
+    /// Returns how far the given metrics are from the target, as the largest difference
+    /// between a targeted ratio and the achieved one, in units of the tolerance. The metrics
+    /// are within tolerance if this is at most 1.
+    pub fn distance(&self, metrics: &Metrics) -> f64 {
+        [
+            (self.solution_ratio, metrics.solution_ratio()),
+            (self.dead_end_ratio, metrics.dead_end_ratio()),
+            (self.decision_ratio, metrics.decision_ratio()),
+        ]
+        .into_iter()
+        .filter_map(|(target, achieved)| target.map(|target| (target - achieved).abs()))
+        .fold(0.0, f64::max)
+            / self.tolerance
+    }
+
+    /// Returns true if the given metrics are within tolerance of the target.
+    pub fn is_met_by(&self, metrics: &Metrics) -> bool {
+        self.distance(metrics) <= 1.0
+    }
+
+    /// Searches for a maze of the given size, with an entrance and exit, that meets the
+    /// target, and returns it with its metrics. This is only a best effort: if none of the
+    /// attempts meets the target, it returns the closest one without any error, so use
+    /// [Difficulty::is_met_by] to check the result.
+    ///
+    /// Each combination of algorithm and opening strategy is tried once, then the remaining
+    /// attempts go to whichever combination has come closest on average so far.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        columns: u32,
+        rows: u32,
+        mut rng: &mut R,
+    ) -> (Maze, Metrics) {
+        let candidates: Vec<(&str, OpeningStrategy)> = CANDIDATE_ALGORITHMS
+            .into_iter()
+            .flat_map(|name| CANDIDATE_STRATEGIES.map(|strategy| (name, strategy)))
+            .collect();
+        let mut totals = vec![(0.0, 0); candidates.len()];
+        let average = |(total, count): (f64, u32)| total / count as f64;
+        let mut best: Option<(Maze, Metrics, f64)> = None;
+        for attempt in 0..self.attempts as usize {
+            let index = if attempt < candidates.len() {
+                attempt
+            } else {
+                (0..candidates.len())
+                    .min_by(|a, b| average(totals[*a]).total_cmp(&average(totals[*b])))
+                    .unwrap()
+            };
+            let (name, strategy) = candidates[index];
+            let mut maze = Maze::new(columns, rows);
+            algorithm(name).unwrap().carve(&mut maze, &mut rng);
+            place_openings(&mut maze, strategy, rng);
+            let metrics = Metrics::measure(&maze).expect("Perfect mazes are solvable");
+            let distance = self.distance(&metrics);
+            totals[index].0 += distance;
+            totals[index].1 += 1;
+            if distance < best.as_ref().map_or(f64::INFINITY, |(_, _, best)| *best) {
+                best = Some((maze, metrics, distance));
+            }
+            if distance <= 1.0 {
+                break;
+            }
+        }
+        let (maze, metrics, _distance) = best.unwrap();
+        (maze, metrics)
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Difficulty {
+        Difficulty::new()
+    }
+}
+
+/// Panics if the given ratio is not between 0.0 and 1.0.
+fn assert_ratio(ratio: f64) {
+    assert!(
+        (0.0..=1.0).contains(&ratio),
+        "Ratio {ratio} is not between 0 and 1"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::cell_edge::CellEdge;
+    use crate::maze::coordinates::Coordinates;
+    use crate::maze::direction::Direction;
+    use crate::maze_generator::{generate_with_seed, test_support};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Returns a 3x3 maze whose solution runs from the south-west corner, through the middle,
+    /// to the north-east corner, with a branch from the middle to a dead end.
+    fn s_maze() -> Maze {
+        let mut maze = Maze::new(3, 3);
+        for (column, row, direction) in [
+            (0, 0, Direction::East),
+            (1, 0, Direction::East),
+            (2, 0, Direction::North),
+            (2, 1, Direction::West),
+            (1, 1, Direction::North),
+            (1, 2, Direction::East),
+            (1, 1, Direction::West),
+            (0, 1, Direction::North),
+        ] {
+            maze.set_edge(
+                &Coordinates::new(column, row),
+                &direction,
+                CellEdge::Passage,
+            );
+        }
+        maze.close_unassigned_edges();
+        maze.set_entrance(&Coordinates::new(0, 0), &Direction::West);
+        maze.set_exit(&Coordinates::new(2, 2), &Direction::East);
+        maze
+    }
+
+    #[test]
+    fn measures_solution() {
+        let metrics = Metrics::measure(&s_maze()).unwrap();
+        assert_eq!(metrics.cells(), 9);
+        assert_eq!(metrics.solution_length(), 7);
+        // Only the middle cell, where the branch to the dead end leaves the solution.
+        assert_eq!(metrics.decision_points(), 1);
+        // The entrance and exit cells count, as they have one passage each.
+        assert_eq!(metrics.dead_ends(), 3);
+    }
+
+    #[test]
+    fn cannot_measure_without_openings() {
+        assert_eq!(Metrics::measure(&generate_with_seed(5, 5, 3)), None);
+    }
+
+    #[test]
+    fn ratios_of_empty_maze_are_zero() {
+        let metrics = Metrics {
+            cells: 0,
+            solution_length: 0,
+            dead_ends: 0,
+            decision_points: 0,
+        };
+        assert_eq!(metrics.solution_ratio(), 0.0);
+        assert_eq!(metrics.dead_end_ratio(), 0.0);
+        assert_eq!(metrics.decision_ratio(), 0.0);
+    }
+
+    #[test]
+    fn presets_get_harder_in_every_ratio() {
+        let presets = [Difficulty::easy(), Difficulty::medium(), Difficulty::hard()];
+        for pair in presets.windows(2) {
+            assert!(pair[0].solution_ratio < pair[1].solution_ratio);
+            assert!(pair[0].dead_end_ratio < pair[1].dead_end_ratio);
+            assert!(pair[0].decision_ratio < pair[1].decision_ratio);
+        }
+    }
+
+    #[test]
+    fn distance_counts_only_targeted_ratios() {
+        let metrics = Metrics::measure(&s_maze()).unwrap();
+        assert_eq!(Difficulty::new().distance(&metrics), 0.0);
+        let difficulty = Difficulty::new()
+            .with_dead_end_ratio(3.0 / 9.0 + 0.1)
+            .with_tolerance(0.2);
+        assert!((difficulty.distance(&metrics) - 0.5).abs() < 1e-9);
+        assert!(difficulty.is_met_by(&metrics));
+        assert!(!difficulty.with_tolerance(0.05).is_met_by(&metrics));
+    }
+
+    #[test]
+    fn generates_within_tolerance() {
+        for difficulty in [Difficulty::easy(), Difficulty::medium(), Difficulty::hard()] {
+            for seed in 0..5 {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let (maze, metrics) = difficulty.generate(20, 15, &mut rng);
+                test_support::assert_consistent(&maze);
+                assert_eq!(Metrics::measure(&maze), Some(metrics));
+                assert!(
+                    difficulty.is_met_by(&metrics),
+                    "{difficulty:?} {metrics:?} seed {seed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not between 0 and 1")]
+    fn rejects_ratio_above_one() {
+        Difficulty::new().with_dead_end_ratio(1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "not between 0 and 1")]
+    fn rejects_nan_ratio() {
+        Difficulty::new().with_solution_ratio(f64::NAN);
+    }
+
+    #[test]
+    fn returns_closest_when_target_is_unreachable() {
+        let mut rng = ChaCha8Rng::seed_from_u64(20);
+        let difficulty = Difficulty::new().with_solution_ratio(1.0).with_attempts(40);
+        let (_maze, metrics) = difficulty.generate(10, 10, &mut rng);
+        assert!(!difficulty.is_met_by(&metrics));
+        assert!(metrics.solution_ratio() > 0.3);
+    }
+}
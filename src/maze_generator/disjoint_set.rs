@@ -0,0 +1,79 @@
+/// A union-find structure over the indices `0..len`, used to track which cells have
+/// already been connected.
+pub(crate) struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl DisjointSet {
+    /// Creates a new DisjointSet in which every index is in a set of its own.
+    pub(crate) fn new(len: usize) -> DisjointSet {
+        DisjointSet {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+        }
+    }
+
+    /// Returns the representative index of the set containing the given index.
+    pub(crate) fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merges the sets containing the given indices. Returns false if they were
+    /// already in the same set.
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> bool {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a == root_b {
+            return false;
+        }
+        match self.ranks[root_a].cmp(&self.ranks[root_b]) {
+            std::cmp::Ordering::Less => self.parents[root_a] = root_b,
+            std::cmp::Ordering::Greater => self.parents[root_b] = root_a,
+            std::cmp::Ordering::Equal => {
+                self.parents[root_b] = root_a;
+                self.ranks[root_a] += 1;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_disjoint() {
+        let mut set = DisjointSet::new(3);
+        assert_ne!(set.find(0), set.find(1));
+        assert_ne!(set.find(1), set.find(2));
+    }
+
+    #[test]
+    fn union_joins_sets() {
+        let mut set = DisjointSet::new(4);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert_eq!(set.find(0), set.find(2));
+    }
+
+    #[test]
+    fn union_of_joined_sets_is_false() {
+        let mut set = DisjointSet::new(3);
+        set.union(0, 1);
+        set.union(1, 2);
+        assert!(!set.union(0, 2));
+    }
+}
@@ -0,0 +1,282 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::braid::braid;
+use crate::maze_generator::{random_level, Constraints, MazeAlgorithm, Room};
+
+/// Builds a roguelike dungeon: open rectangular rooms are scattered across the maze without
+/// overlapping or touching, the gaps between them are filled with maze corridors, and each
+/// room is joined to the corridors by one or more doorways.
+///
+/// With a single doorway per room and no braiding, every cell outside the rooms can be reached
+/// by exactly one route. Extra doorways and braiding add loops. In a maze with more than one
+/// level, the rooms are scattered across all of the levels, which are joined by stairs.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Dungeon {
+    room_attempts: u32,
+    min_room_size: u32,
+    max_room_size: u32,
+    doorways: u32,
+    braid: f64,
+}
+
+impl Dungeon {
+    /// Creates a new Dungeon that tries to place 20 rooms between 3 and 6 cells wide and
+    /// high, each with up to 2 doorways, and keeps its dead ends.
+    pub fn new() -> Dungeon {
+        Dungeon {
+            room_attempts: 20,
+            min_room_size: 3,
+            max_room_size: 6,
+            doorways: 2,
+            braid: 0.0,
+        }
+    }
+
+    /// Returns the Dungeon with the given number of attempts at placing a room. Attempts
+    /// that would overlap or touch an earlier room are dropped, so this is the most rooms
+    /// that are placed.
+    pub fn with_room_attempts(mut self, room_attempts: u32) -> Dungeon {
+        self.room_attempts = room_attempts;
+        self
+    }
+
+    /// Returns the Dungeon with rooms whose widths and heights are between the given sizes,
+    /// inclusive.
+    pub fn with_room_size(mut self, min_room_size: u32, max_room_size: u32) -> Dungeon {
+        assert!(min_room_size > 0, "Room size must be at least 1");
+        assert!(
+            min_room_size <= max_room_size,
+            "Minimum room size {min_room_size} is greater than maximum {max_room_size}"
+        );
+        self.min_room_size = min_room_size;
+        self.max_room_size = max_room_size;
+        self
+    }
+
+    /// Returns the Dungeon with up to the given number of doorways into each room. Every
+    /// room has at least one doorway, and a room has fewer than requested if it has fewer
+    /// walls facing the corridors.
+    pub fn with_doorways(mut self, doorways: u32) -> Dungeon {
+        assert!(doorways > 0, "Rooms must have at least 1 doorway");
+        self.doorways = doorways;
+        self
+    }
+
+    /// Returns the Dungeon with the given fraction (between 0.0 and 1.0) of the corridors'
+    /// dead ends removed once it is carved. See [braid].
+    pub fn with_braid(mut self, fraction: f64) -> Dungeon {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "Fraction {fraction} is not between 0 and 1"
+        );
+        self.braid = fraction;
+        self
+    }
+
+    /// Carves the given unpopulated maze into a dungeon, and returns the rooms that were
+    /// placed in it. A maze with no active cells is left as it is, with no rooms.
+    pub fn carve_rooms<R: Rng + ?Sized>(&self, maze: &mut Maze, mut rng: &mut R) -> Vec<Room> {
+        if maze.active_count() == 0 {
+            return Vec::new();
+        }
+        let rooms = self.scatter_rooms(maze, rng);
+
+        // Kruskal treats each room as already connected, so its spanning tree joins each
+        // room to the corridors through exactly one doorway.
+        let constraints = rooms.iter().fold(Constraints::new(), |constraints, room| {
+            constraints.with_room(*room)
+        });
+        constraints
+            .carve(maze, &mut rng)
+            .expect("Rooms are placed inside the maze without overlapping");
+
+        for room in &rooms {
+            self.add_doorways(maze, room, rng);
+        }
+        if self.braid > 0.0 {
+            braid(maze, self.braid, rng);
+        }
+        rooms
+    }
+
+    fn scatter_rooms<R: Rng + ?Sized>(&self, maze: &Maze, rng: &mut R) -> Vec<Room> {
+        let mut rooms: Vec<Room> = Vec::new();
+        for _ in 0..self.room_attempts {
+            let width = rng.gen_range(self.min_room_size..=self.max_room_size);
+            let height = rng.gen_range(self.min_room_size..=self.max_room_size);
+            if width > maze.columns() || height > maze.rows() {
+                continue;
+            }
+            let column = rng.gen_range(0..=maze.columns() - width) as i32;
+            let row = rng.gen_range(0..=maze.rows() - height) as i32;
+            let level = random_level(maze, rng);
+            let room = Room::new(Coordinates::with_level(column, row, level), width, height);
+            if rooms.iter().all(|other| !touches(&room, other))
+                && cells(&room).all(|coordinates| maze.is_active(&coordinates))
+            {
+                rooms.push(room);
+            }
+        }
+        rooms
+    }
+
+    fn add_doorways<R: Rng + ?Sized>(&self, maze: &mut Maze, room: &Room, rng: &mut R) {
+        let mut doorways = 0;
+        let mut walls = Vec::new();
+        for coordinates in cells(room) {
+            for direction in Direction::ALL {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if room.contains(&neighbor_coordinates) {
+                    continue;
+                }
+                match maze.cell(&coordinates).unwrap().edge(&direction) {
+                    Some(CellEdge::Passage) => doorways += 1,
+                    Some(CellEdge::Wall) => walls.push((coordinates, direction)),
+                    _ => {}
+                }
+            }
+        }
+        walls.shuffle(rng);
+        for (coordinates, direction) in walls
+            .into_iter()
+            .take(self.doorways.saturating_sub(doorways) as usize)
+        {
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+        }
+    }
+}
+
+impl Default for Dungeon {
+    fn default() -> Dungeon {
+        Dungeon::new()
+    }
+}
+
+impl MazeAlgorithm for Dungeon {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        self.carve_rooms(maze, rng);
+    }
+}
+
+/// Returns the coordinates of every cell in the room.
+fn cells(room: &Room) -> impl Iterator<Item = Coordinates> {
+    let start = room.coordinates();
+    let (width, height) = (room.width() as i32, room.height() as i32);
+    (0..height).flat_map(move |row| {
+        (0..width).map(move |column| {
+            Coordinates::with_level(start.column() + column, start.row() + row, start.level())
+        })
+    })
+}
+
+/// Returns true if the rooms are on the same level, and overlap or are next to each other with
+/// no corridor between them.
+fn touches(room: &Room, other: &Room) -> bool {
+    let (a, b) = (room.coordinates(), other.coordinates());
+    a.level() == b.level()
+        && a.column() <= b.column() + other.width() as i32
+        && b.column() <= a.column() + room.width() as i32
+        && a.row() <= b.row() + other.height() as i32
+        && b.row() <= a.row() + room.height() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::{dead_ends, test_support};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_connected_dungeon() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::new(30, 20);
+        let rooms = Dungeon::new().carve_rooms(&mut maze, &mut rng);
+        assert!(rooms.len() > 1);
+        test_support::assert_consistent(&maze);
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            600
+        );
+    }
+
+    #[test]
+    fn rooms_are_open_and_apart() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::new(30, 20);
+        let rooms = Dungeon::new().carve_rooms(&mut maze, &mut rng);
+        for (index, room) in rooms.iter().enumerate() {
+            for (coordinates, direction) in room.interior_edges() {
+                let cell = maze.cell(&coordinates).unwrap();
+                assert_eq!(cell.edge(&direction), Some(CellEdge::Passage));
+            }
+            for other in &rooms[index + 1..] {
+                assert!(!touches(room, other));
+            }
+        }
+    }
+
+    #[test]
+    fn single_doorway_leaves_corridors_perfect() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::new(30, 20);
+        let rooms = Dungeon::new()
+            .with_doorways(1)
+            .carve_rooms(&mut maze, &mut rng);
+        // A tree has one passage fewer than it has cells; each room's own loops add the rest.
+        let room_loops: usize = rooms
+            .iter()
+            .map(|room| room.interior_edges().len() + 1 - (room.width() * room.height()) as usize)
+            .sum();
+        assert_eq!(test_support::passage_count(&maze), 600 - 1 + room_loops);
+    }
+
+    #[test]
+    fn empty_maze_has_no_rooms() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::new(0, 0);
+        assert!(Dungeon::new().carve_rooms(&mut maze, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn scatters_rooms_across_levels() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::with_levels(20, 15, 3);
+        let rooms = Dungeon::new().carve_rooms(&mut maze, &mut rng);
+        test_support::assert_consistent(&maze);
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            900
+        );
+        for level in 0..3 {
+            assert!(rooms.iter().any(|room| room.coordinates().level() == level));
+        }
+    }
+
+    #[test]
+    fn braiding_removes_dead_ends() {
+        let mut maze = Maze::new(30, 20);
+        Dungeon::new().carve(&mut maze, &mut ChaCha8Rng::seed_from_u64(19));
+        let mut braided = Maze::new(30, 20);
+        Dungeon::new()
+            .with_braid(1.0)
+            .carve(&mut braided, &mut ChaCha8Rng::seed_from_u64(19));
+        assert!(dead_ends(&braided).len() < dead_ends(&maze).len());
+    }
+
+    #[test]
+    fn skips_rooms_that_do_not_fit() {
+        let mut rng = ChaCha8Rng::seed_from_u64(19);
+        let mut maze = Maze::new(4, 4);
+        let rooms = Dungeon::new()
+            .with_room_size(5, 6)
+            .carve_rooms(&mut maze, &mut rng);
+        assert!(rooms.is_empty());
+        test_support::assert_perfect(&maze);
+    }
+}
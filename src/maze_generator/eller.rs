@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::maze::cell::Cell;
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{carve_by_level, MazeAlgorithm};
+
+/// Carves a maze with Eller's algorithm, one row at a time from the southernmost row up,
+/// keeping track of which cells in the current row are already connected.
+///
+/// Use [Eller::rows] to stream the rows without building a whole [Maze]. It cannot carve a
+/// masked maze, and carves levels with [carve_by_level].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Eller;
+
+impl Eller {
+    /// Returns an iterator over the completed rows of a maze of the given size, from row 0
+    /// northwards. Only one row of state is held in memory, however tall the maze.
+    pub fn rows<R: Rng>(columns: u32, rows: u32, rng: R) -> EllerRows<R> {
+        EllerRows {
+            columns,
+            rows,
+            row: 0,
+            sets: vec![None; columns as usize],
+            south_passages: vec![false; columns as usize],
+            rng,
+        }
+    }
+}
+
+impl MazeAlgorithm for Eller {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        assert!(maze.mask().is_none(), "Eller cannot carve a masked maze");
+        if maze.levels() > 1 {
+            carve_by_level(self, maze, rng);
+            return;
+        }
+        for row in Eller::rows(maze.columns(), maze.rows(), rng) {
+            for cell in row {
+                maze.set_cell(cell);
+            }
+        }
+    }
+}
+
+/// An iterator over the rows of a maze carved with Eller's algorithm. Each row is a vector
+/// of fully assigned [Cell]s, ordered from west to east.
+#[derive(Debug)]
+pub struct EllerRows<R> {
+    columns: u32,
+    rows: u32,
+    row: u32,
+    // The set each cell in the current row belongs to, or None if it is not yet connected
+    // to the row below. There can never be more sets than columns, so ids are reused.
+    sets: Vec<Option<usize>>,
+    // Whether each cell in the current row has a passage to the row below.
+    south_passages: Vec<bool>,
+    rng: R,
+}
+
+impl<R: Rng> EllerRows<R> {
+    fn assign_new_sets(&mut self) {
+        let mut used = vec![false; self.sets.len()];
+        for set in self.sets.iter().flatten() {
+            used[*set] = true;
+        }
+        let mut free = (0..used.len()).filter(|set| !used[*set]);
+        for set in self.sets.iter_mut().filter(|set| set.is_none()) {
+            *set = free.next();
+        }
+    }
+
+    fn merge_sets(&mut self, from: usize, to: usize) {
+        for set in self.sets.iter_mut() {
+            if *set == Some(from) {
+                *set = Some(to);
+            }
+        }
+    }
+
+    /// Joins neighboring cells in different sets, always on the last row and at random
+    /// otherwise. Returns whether each cell has a passage to its east.
+    fn join_row(&mut self, last_row: bool) -> Vec<bool> {
+        let mut east_passages = vec![false; self.sets.len()];
+        for column in 1..self.sets.len() {
+            let (west, east) = (self.sets[column - 1].unwrap(), self.sets[column].unwrap());
+            if west != east && (last_row || self.rng.gen_bool(0.5)) {
+                east_passages[column - 1] = true;
+                self.merge_sets(east, west);
+            }
+        }
+        east_passages
+    }
+
+    /// Connects every set to the next row at least once, and each cell at random otherwise.
+    /// Returns whether each cell has a passage to its north.
+    fn join_next_row(&mut self) -> Vec<bool> {
+        let mut columns_by_set: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (column, set) in self.sets.iter().enumerate() {
+            columns_by_set.entry(set.unwrap()).or_default().push(column);
+        }
+        let mut north_passages = vec![false; self.sets.len()];
+        for columns in columns_by_set.values_mut() {
+            columns.shuffle(&mut self.rng);
+            north_passages[columns[0]] = true;
+            for column in &columns[1..] {
+                north_passages[*column] = self.rng.gen_bool(0.5);
+            }
+        }
+        north_passages
+    }
+}
+
+impl<R: Rng> Iterator for EllerRows<R> {
+    type Item = Vec<Cell>;
+
+    fn next(&mut self) -> Option<Vec<Cell>> {
+        if self.row >= self.rows {
+            return None;
+        }
+        let last_row = self.row + 1 == self.rows;
+        self.assign_new_sets();
+        let east_passages = self.join_row(last_row);
+        let north_passages = if last_row {
+            vec![false; self.sets.len()]
+        } else {
+            self.join_next_row()
+        };
+
+        let edge = |passage: bool| {
+            if passage {
+                CellEdge::Passage
+            } else {
+                CellEdge::Wall
+            }
+        };
+        let mut cells = Vec::with_capacity(self.sets.len());
+        for column in 0..self.columns as usize {
+            let mut cell = Cell::new(Coordinates::new(column as i32, self.row as i32));
+            let west_edge = if column == 0 {
+                CellEdge::Border
+            } else {
+                edge(east_passages[column - 1])
+            };
+            let east_edge = if column + 1 == self.sets.len() {
+                CellEdge::Border
+            } else {
+                edge(east_passages[column])
+            };
+            let south_edge = if self.row == 0 {
+                CellEdge::Border
+            } else {
+                edge(self.south_passages[column])
+            };
+            let north_edge = if last_row {
+                CellEdge::Border
+            } else {
+                edge(north_passages[column])
+            };
+            cell.set_edge(&Direction::North, Some(north_edge));
+            cell.set_edge(&Direction::East, Some(east_edge));
+            cell.set_edge(&Direction::South, Some(south_edge));
+            cell.set_edge(&Direction::West, Some(west_edge));
+            cells.push(cell);
+        }
+
+        for (set, passage) in self.sets.iter_mut().zip(&north_passages) {
+            if !passage {
+                *set = None;
+            }
+        }
+        self.south_passages = north_passages;
+        self.row += 1;
+        Some(cells)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.rows - self.row) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(6);
+        let mut maze = Maze::new(18, 12);
+        Eller.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_row_and_single_column_mazes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(6);
+        for (columns, rows) in [(10, 1), (1, 10), (1, 1)] {
+            let mut maze = Maze::new(columns, rows);
+            Eller.carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn rows_streams_every_row_in_order() {
+        let rows: Vec<Vec<Cell>> = Eller::rows(7, 30, ChaCha8Rng::seed_from_u64(6)).collect();
+        assert_eq!(rows.len(), 30);
+        for (row, cells) in rows.iter().enumerate() {
+            assert_eq!(cells.len(), 7);
+            for (column, cell) in cells.iter().enumerate() {
+                assert_eq!(
+                    cell.coordinates(),
+                    Coordinates::new(column as i32, row as i32)
+                );
+                assert!(cell.is_fully_assigned());
+            }
+        }
+    }
+
+    #[test]
+    fn rows_matches_carve_for_same_seed() {
+        let mut maze = Maze::new(9, 9);
+        Eller.carve(&mut maze, &mut ChaCha8Rng::seed_from_u64(6));
+        let streamed: Vec<Option<Cell>> = Eller::rows(9, 9, ChaCha8Rng::seed_from_u64(6))
+            .flatten()
+            .map(Some)
+            .collect();
+        assert_eq!(maze.into_iter().collect::<Vec<_>>(), streamed);
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, RngCore};
+
+use crate::maze::cell::Cell;
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::bias::Bias;
+use crate::maze_generator::{random_coordinates, MazeAlgorithm};
+
+/// Enumerates the ways a [GrowingTree] can select the next active cell to grow from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    /// The most recently added cell. Behaves like [RecursiveBacktracker](super::RecursiveBacktracker).
+    Newest,
+    /// The least recently added cell. Produces long, straight corridors radiating from the start.
+    Oldest,
+    /// A random cell. Behaves like [SimplifiedPrim](super::SimplifiedPrim).
+    Random,
+    /// The cell in the middle of the active list. The list has to stay in order, so removing
+    /// a cell from it takes time in proportion to its length.
+    Middle,
+    /// One of the given selections, chosen at random in proportion to its weight.
+    /// For example, `[(Newest, 3), (Random, 1)]` selects the newest cell 75% of the time.
+    Weighted(Vec<(Selection, u32)>),
+}
+
+/// Associated functions to use a Selection enum.
+impl Selection {
+    /// Returns the index of the next cell in an active list of the given length.
+    fn index<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> usize {
+        match self {
+            Selection::Newest => len - 1,
+            Selection::Oldest => 0,
+            Selection::Random => rng.gen_range(0..len as u32) as usize,
+            Selection::Middle => len / 2,
+            Selection::Weighted(selections) => {
+                // Summed as u64, so that no number of u32 weights can overflow.
+                let total: u64 = selections
+                    .iter()
+                    .map(|(_selection, weight)| *weight as u64)
+                    .sum();
+                assert!(total > 0, "Weighted selection has no weight");
+                let mut choice = rng.gen_range(0..total);
+                for (selection, weight) in selections {
+                    if choice < *weight as u64 {
+                        return selection.index(len, rng);
+                    }
+                    choice -= *weight as u64;
+                }
+                unreachable!()
+            }
+        }
+    }
+}
+
+/// Carves a maze with the Growing Tree algorithm: a list of active cells starts with one random
+/// cell, and the maze grows from a cell selected from the list, which is removed once it has no
+/// unassigned edges left. The [Selection] strategy controls the texture of the maze, and an
+/// optional [Bias] controls the direction it grows in from each cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrowingTree {
+    selection: Selection,
+    bias: Option<Bias>,
+}
+
+impl GrowingTree {
+    /// Creates a new GrowingTree that uses the given selection strategy, and chooses
+    /// directions uniformly.
+    pub fn new(selection: Selection) -> GrowingTree {
+        GrowingTree {
+            selection,
+            bias: None,
+        }
+    }
+
+    /// Returns the GrowingTree with the given bias for choosing directions.
+    pub fn with_bias(mut self, bias: Bias) -> GrowingTree {
+        self.bias = Some(bias);
+        self
+    }
+
+    /// Returns the GrowingTree's selection strategy.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    /// Returns the GrowingTree's bias for choosing directions, if it has one.
+    pub fn bias(&self) -> Option<Bias> {
+        self.bias
+    }
+}
+
+/// Selects the newest cell, like [RecursiveBacktracker](super::RecursiveBacktracker).
+impl Default for GrowingTree {
+    fn default() -> GrowingTree {
+        GrowingTree::new(Selection::Newest)
+    }
+}
+
+impl MazeAlgorithm for GrowingTree {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let unpopulated = std::mem::replace(maze, Maze::new(0, 0));
+        let mut steps = self.steps(unpopulated, rng);
+        for _event in &mut steps {}
+        *maze = steps.into_maze();
+    }
+}
+
+/// Enumerates the events that happen while a [GrowingTree] carves a maze.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GenerationEvent {
+    /// The cell at the coordinates was visited for the first time, and became active.
+    CellVisited(Coordinates),
+    /// A passage was carved from the cell at the coordinates in the direction.
+    PassageCarved(Coordinates, Direction),
+    /// A wall was placed between the cell at the coordinates and an already visited
+    /// neighbor in the direction.
+    WallPlaced(Coordinates, Direction),
+    /// A border was placed on the edge of the cell at the coordinates in the direction.
+    BorderPlaced(Coordinates, Direction),
+    /// The cell at the coordinates had no unassigned edges left, so it stopped being active.
+    Backtracked(Coordinates),
+}
+
+impl GrowingTree {
+    /// Returns an iterator that carves the given unpopulated maze one event at a time. The
+    /// partially carved maze can be inspected between events with [GenerationSteps::maze].
+    pub fn steps<R: Rng>(&self, maze: Maze, rng: R) -> GenerationSteps<R> {
+        GenerationSteps {
+            maze,
+            selection: self.selection.clone(),
+            bias: self.bias,
+            active_cells: VecDeque::new(),
+            events: VecDeque::new(),
+            started: false,
+            rng,
+        }
+    }
+}
+
+/// An active cell's coordinates, with the direction of the passage that led to it.
+type ActiveCell = (Coordinates, Option<Direction>);
+
+/// An iterator over the [GenerationEvent]s of a [GrowingTree] carving a maze. Each call to
+/// `next` carves a little more of the maze.
+#[derive(Debug)]
+pub struct GenerationSteps<R> {
+    maze: Maze,
+    selection: Selection,
+    bias: Option<Bias>,
+    active_cells: VecDeque<ActiveCell>,
+    events: VecDeque<GenerationEvent>,
+    started: bool,
+    rng: R,
+}
+
+impl<R: Rng> GenerationSteps<R> {
+    /// Returns the maze as carved so far.
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    /// Returns the maze as carved so far, consuming the iterator.
+    pub fn into_maze(self) -> Maze {
+        self.maze
+    }
+
+    fn process_active_cell(&mut self) {
+        let index = self.selection.index(self.active_cells.len(), &mut self.rng);
+        let (cell_coordinates, previous_direction) = self.active_cells[index];
+        if let Some(mut current_cell) = self.maze.cell(&cell_coordinates) {
+            if current_cell.is_fully_assigned() {
+                if self.selection == Selection::Random {
+                    // Random selection doesn't depend on the order of the active list, so the
+                    // cell can be swapped with the last one and popped, rather than shifting
+                    // everything after it.
+                    self.active_cells.swap_remove_back(index);
+                } else {
+                    self.active_cells.remove(index);
+                }
+                self.events
+                    .push_back(GenerationEvent::Backtracked(cell_coordinates));
+                return;
+            }
+            let direction = match self.bias {
+                Some(bias) => bias.choose(
+                    &current_cell.unassigned_directions(),
+                    previous_direction,
+                    &mut self.rng,
+                ),
+                None => current_cell.random_unassigned_direction(&mut self.rng),
+            };
+            let neighbor_coordinates = current_cell.coordinates() + direction.coordinates();
+            if self.maze.is_active(&neighbor_coordinates) {
+                match self.maze.cell(&neighbor_coordinates) {
+                    Some(mut neighbor_cell) => {
+                        self.create_wall(&mut current_cell, &mut neighbor_cell, &direction)
+                    }
+                    None => {
+                        // The neighbor hasn't been visited, so create cell there with a passage
+                        let mut neighbor_cell =
+                            self.add_new_cell(neighbor_coordinates, Some(direction));
+                        self.create_passage(&mut current_cell, &mut neighbor_cell, &direction);
+                    }
+                }
+            } else {
+                // Neighbor is out of bounds or masked out, so create a border in the selected direction
+                self.create_border(&mut current_cell, &direction);
+            }
+        }
+    }
+
+    fn add_first_cell(&mut self) {
+        let coordinates = random_coordinates(&self.maze, &mut self.rng);
+        self.add_new_cell(coordinates, None);
+    }
+
+    fn add_new_cell(&mut self, coordinates: Coordinates, direction: Option<Direction>) -> Cell {
+        let new_cell = self.maze.new_cell(coordinates);
+        self.maze.set_cell(new_cell);
+        self.active_cells.push_back((coordinates, direction));
+        self.events
+            .push_back(GenerationEvent::CellVisited(coordinates));
+        new_cell
+    }
+
+    fn create_passage(
+        &mut self,
+        target_cell: &mut Cell,
+        neighbor_cell: &mut Cell,
+        direction: &Direction,
+    ) {
+        self.create_edge(target_cell, neighbor_cell, direction, CellEdge::Passage);
+        self.events.push_back(GenerationEvent::PassageCarved(
+            target_cell.coordinates(),
+            *direction,
+        ));
+    }
+
+    fn create_wall(
+        &mut self,
+        target_cell: &mut Cell,
+        neighbor_cell: &mut Cell,
+        direction: &Direction,
+    ) {
+        self.create_edge(target_cell, neighbor_cell, direction, CellEdge::Wall);
+        self.events.push_back(GenerationEvent::WallPlaced(
+            target_cell.coordinates(),
+            *direction,
+        ));
+    }
+
+    fn create_edge(
+        &mut self,
+        target_cell: &mut Cell,
+        neighbor_cell: &mut Cell,
+        direction: &Direction,
+        cell_edge: CellEdge,
+    ) {
+        target_cell.set_edge(direction, Some(cell_edge));
+        self.maze.set_cell(*target_cell);
+        neighbor_cell.set_edge(&direction.opposite(), Some(cell_edge));
+        self.maze.set_cell(*neighbor_cell);
+    }
+
+    fn create_border(&mut self, cell: &mut Cell, direction: &Direction) {
+        cell.set_edge(direction, Some(CellEdge::Border));
+        self.maze.set_cell(*cell);
+        self.events.push_back(GenerationEvent::BorderPlaced(
+            cell.coordinates(),
+            *direction,
+        ));
+    }
+}
+
+impl<R: Rng> Iterator for GenerationSteps<R> {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(event);
+            }
+            if !self.started {
+                self.started = true;
+                self.add_first_cell();
+            } else if self.active_cells.is_empty() {
+                return None;
+            } else {
+                self.process_active_cell();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_carves_perfect_maze(selection: Selection) {
+        let mut rng = ChaCha8Rng::seed_from_u64(14);
+        let mut maze = Maze::new(15, 10);
+        GrowingTree::new(selection).carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn newest_carves_perfect_maze() {
+        assert_carves_perfect_maze(Selection::Newest);
+    }
+
+    #[test]
+    fn oldest_carves_perfect_maze() {
+        assert_carves_perfect_maze(Selection::Oldest);
+    }
+
+    #[test]
+    fn random_carves_perfect_maze() {
+        assert_carves_perfect_maze(Selection::Random);
+    }
+
+    #[test]
+    fn middle_carves_perfect_maze() {
+        assert_carves_perfect_maze(Selection::Middle);
+    }
+
+    #[test]
+    fn weighted_carves_perfect_maze() {
+        assert_carves_perfect_maze(Selection::Weighted(vec![
+            (Selection::Newest, 3),
+            (Selection::Random, 1),
+        ]));
+    }
+
+    #[test]
+    fn biased_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(14);
+        let mut maze = Maze::new(15, 10);
+        GrowingTree::new(Selection::Random)
+            .with_bias(Bias::new(0.8, 0.6))
+            .carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    fn horizontal_and_vertical_passages(maze: &Maze) -> (usize, usize) {
+        let mut counts = (0, 0);
+        for cell in maze.into_iter().flatten() {
+            if cell.edge(&Direction::East) == Some(CellEdge::Passage) {
+                counts.0 += 1;
+            }
+            if cell.edge(&Direction::North) == Some(CellEdge::Passage) {
+                counts.1 += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn horizontal_bias_favors_horizontal_passages() {
+        let mut rng = ChaCha8Rng::seed_from_u64(14);
+        let mut maze = Maze::new(30, 30);
+        GrowingTree::new(Selection::Newest)
+            .with_bias(Bias::new(0.9, 0.0))
+            .carve(&mut maze, &mut rng);
+        let (horizontal, vertical) = horizontal_and_vertical_passages(&maze);
+        assert!(horizontal > vertical * 2, "{horizontal} vs {vertical}");
+    }
+
+    /// Returns the number of cells whose passages continue straight through them.
+    fn straight_cells(maze: &Maze) -> usize {
+        maze.into_iter()
+            .flatten()
+            .filter(|cell| {
+                let passage = |direction| cell.edge(&direction) == Some(CellEdge::Passage);
+                (passage(Direction::North) && passage(Direction::South))
+                    || (passage(Direction::East) && passage(Direction::West))
+            })
+            .count()
+    }
+
+    #[test]
+    fn straightness_makes_straighter_passages() {
+        let mut twisty = Maze::new(30, 30);
+        let mut straight = Maze::new(30, 30);
+        GrowingTree::new(Selection::Newest)
+            .with_bias(Bias::new(0.5, 0.0))
+            .carve(&mut twisty, &mut ChaCha8Rng::seed_from_u64(14));
+        GrowingTree::new(Selection::Newest)
+            .with_bias(Bias::new(0.5, 0.9))
+            .carve(&mut straight, &mut ChaCha8Rng::seed_from_u64(14));
+        assert!(straight_cells(&straight) > straight_cells(&twisty) * 3 / 2);
+    }
+
+    #[test]
+    fn steps_match_carve() {
+        let mut carved = Maze::new(9, 7);
+        GrowingTree::new(Selection::Random).carve(&mut carved, &mut ChaCha8Rng::seed_from_u64(2));
+        let mut steps = GrowingTree::new(Selection::Random)
+            .steps(Maze::new(9, 7), ChaCha8Rng::seed_from_u64(2));
+        for _event in &mut steps {}
+        assert_eq!(steps.into_maze(), carved);
+    }
+
+    #[test]
+    fn steps_report_every_change() {
+        let mut steps = GrowingTree::default().steps(Maze::new(6, 4), ChaCha8Rng::seed_from_u64(2));
+        let (mut visited, mut passages, mut walls, mut borders, mut backtracked) = (0, 0, 0, 0, 0);
+        let mut previous = steps.maze().clone();
+        while let Some(event) = steps.next() {
+            let changed = match event {
+                GenerationEvent::CellVisited(coordinates) => {
+                    visited += 1;
+                    assert_eq!(previous.cell(&coordinates), None);
+                    steps.maze().cell(&coordinates).is_some()
+                }
+                GenerationEvent::PassageCarved(coordinates, direction) => {
+                    passages += 1;
+                    let cell = steps.maze().cell(&coordinates).unwrap();
+                    cell.edge(&direction) == Some(CellEdge::Passage)
+                }
+                GenerationEvent::WallPlaced(coordinates, direction) => {
+                    walls += 1;
+                    let cell = steps.maze().cell(&coordinates).unwrap();
+                    cell.edge(&direction) == Some(CellEdge::Wall)
+                }
+                GenerationEvent::BorderPlaced(coordinates, direction) => {
+                    borders += 1;
+                    let cell = steps.maze().cell(&coordinates).unwrap();
+                    cell.edge(&direction) == Some(CellEdge::Border)
+                }
+                GenerationEvent::Backtracked(coordinates) => {
+                    backtracked += 1;
+                    steps.maze().cell(&coordinates).unwrap().is_fully_assigned()
+                }
+            };
+            assert!(changed, "{event:?} not reflected in maze");
+            previous = steps.maze().clone();
+        }
+        // A 6x4 perfect maze has 23 passages, 38 interior edges and 20 border edges.
+        assert_eq!(
+            (visited, passages, walls, borders, backtracked),
+            (24, 23, 15, 20, 24)
+        );
+        test_support::assert_perfect(steps.maze());
+    }
+
+    #[test]
+    fn weighted_with_one_selection_matches_it() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let weighted = Selection::Weighted(vec![(Selection::Oldest, 1), (Selection::Newest, 0)]);
+        for len in 1..10 {
+            assert_eq!(weighted.index(len, &mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn weighted_with_large_weights_does_not_overflow() {
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let weighted = Selection::Weighted(vec![
+            (Selection::Oldest, u32::MAX),
+            (Selection::Newest, u32::MAX),
+        ]);
+        for _ in 0..10 {
+            assert!([0, 2].contains(&weighted.index(3, &mut rng)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no weight")]
+    fn weighted_without_weight_panics() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        Selection::Weighted(vec![(Selection::Oldest, 0)]).index(3, &mut rng);
+    }
+}
@@ -0,0 +1,188 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::hex::coordinates::HexCoordinates;
+use crate::maze::hex::direction::HexDirection;
+use crate::maze::hex::HexMaze;
+use crate::maze_generator::disjoint_set::DisjointSet;
+use crate::maze_generator::{Kruskal, RecursiveBacktracker};
+
+/// An algorithm that carves an unpopulated [HexMaze] into a perfect maze.
+pub trait HexMazeAlgorithm {
+    /// Populates the given [HexMaze] with fully assigned cells, using the given random number
+    /// generator for every random choice.
+    fn carve_hex(&self, maze: &mut HexMaze, rng: &mut dyn RngCore);
+}
+
+impl HexMazeAlgorithm for RecursiveBacktracker {
+    fn carve_hex(&self, maze: &mut HexMaze, rng: &mut dyn RngCore) {
+        let start = random_coordinates(maze, rng);
+        let mut visited = vec![false; maze.cell_count()];
+        visited[maze.get_index(&start)] = true;
+        let mut stack = vec![start];
+        while let Some(coordinates) = stack.last().copied() {
+            let neighbors: Vec<HexDirection> = HexDirection::ALL
+                .into_iter()
+                .filter(|direction| {
+                    let neighbor_coordinates = coordinates + direction.coordinates();
+                    maze.in_bounds(&neighbor_coordinates)
+                        && !visited[maze.get_index(&neighbor_coordinates)]
+                })
+                .collect();
+            if neighbors.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let direction = neighbors[rng.gen_range(0..neighbors.len() as u32) as usize];
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            visited[maze.get_index(&neighbor_coordinates)] = true;
+            stack.push(neighbor_coordinates);
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+impl HexMazeAlgorithm for Kruskal {
+    fn carve_hex(&self, maze: &mut HexMaze, rng: &mut dyn RngCore) {
+        let mut sets = DisjointSet::new(maze.cell_count());
+        // Each interior edge is listed once, from the cell to its west.
+        let mut edges: Vec<(HexCoordinates, HexDirection)> = Vec::new();
+        for coordinates in maze.coordinates() {
+            for direction in [
+                HexDirection::NorthEast,
+                HexDirection::East,
+                HexDirection::SouthEast,
+            ] {
+                if maze.in_bounds(&(coordinates + direction.coordinates())) {
+                    edges.push((coordinates, direction));
+                }
+            }
+        }
+        edges.shuffle(rng);
+
+        for (coordinates, direction) in edges {
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            if sets.union(
+                maze.get_index(&coordinates),
+                maze.get_index(&neighbor_coordinates),
+            ) {
+                maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            }
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Returns the coordinates of every dead end in the hex maze.
+pub fn hex_dead_ends(maze: &HexMaze) -> Vec<HexCoordinates> {
+    maze.into_iter()
+        .flatten()
+        .filter(|cell| cell.is_dead_end())
+        .map(|cell| cell.coordinates())
+        .collect()
+}
+
+/// Returns the coordinates of a random cell in the hex maze.
+fn random_coordinates<R: Rng + ?Sized>(maze: &HexMaze, rng: &mut R) -> HexCoordinates {
+    let column = rng.gen_range(0..maze.columns());
+    let row = rng.gen_range(0..maze.rows());
+    HexCoordinates::from_offset(column as i32, row as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Asserts that every cell in the hex maze is fully assigned, that neighbors agree on
+    /// the edges between them, and that every cell can be reached by exactly one route.
+    fn assert_perfect(maze: &HexMaze) {
+        let mut passages = 0;
+        for coordinates in maze.coordinates() {
+            let cell = maze.cell(&coordinates).expect("Cell is populated");
+            assert!(cell.is_fully_assigned(), "{coordinates:?} is not assigned");
+            for direction in HexDirection::ALL {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if !maze.in_bounds(&neighbor_coordinates) {
+                    assert_eq!(cell.edge(&direction), Some(CellEdge::Border));
+                    continue;
+                }
+                let neighbor = maze.cell(&neighbor_coordinates).unwrap();
+                assert_eq!(cell.edge(&direction), neighbor.edge(&direction.opposite()));
+                if cell.edge(&direction) == Some(CellEdge::Passage) {
+                    passages += 1;
+                }
+            }
+        }
+        // Each passage was counted from both of its cells.
+        assert_eq!(passages / 2, maze.cell_count() - 1);
+
+        let mut visited = vec![false; maze.cell_count()];
+        let mut stack = vec![HexCoordinates::from_offset(0, 0)];
+        visited[0] = true;
+        while let Some(coordinates) = stack.pop() {
+            let cell = maze.cell(&coordinates).unwrap();
+            for direction in HexDirection::ALL {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if cell.edge(&direction) == Some(CellEdge::Passage)
+                    && !visited[maze.get_index(&neighbor_coordinates)]
+                {
+                    visited[maze.get_index(&neighbor_coordinates)] = true;
+                    stack.push(neighbor_coordinates);
+                }
+            }
+        }
+        assert!(visited.iter().all(|visited| *visited));
+    }
+
+    #[test]
+    fn recursive_backtracker_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let mut maze = HexMaze::new(13, 9);
+        RecursiveBacktracker.carve_hex(&mut maze, &mut rng);
+        assert_perfect(&maze);
+    }
+
+    #[test]
+    fn kruskal_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let mut maze = HexMaze::new(13, 9);
+        Kruskal.carve_hex(&mut maze, &mut rng);
+        assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_cell_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let mut maze = HexMaze::new(1, 1);
+        RecursiveBacktracker.carve_hex(&mut maze, &mut rng);
+        assert_perfect(&maze);
+    }
+
+    #[test]
+    fn same_seed_carves_same_maze() {
+        let mut maze1 = HexMaze::new(8, 8);
+        let mut maze2 = HexMaze::new(8, 8);
+        Kruskal.carve_hex(&mut maze1, &mut ChaCha8Rng::seed_from_u64(4));
+        Kruskal.carve_hex(&mut maze2, &mut ChaCha8Rng::seed_from_u64(4));
+        assert_eq!(maze1, maze2);
+    }
+
+    #[test]
+    fn finds_dead_ends() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let mut maze = HexMaze::new(3, 1);
+        RecursiveBacktracker.carve_hex(&mut maze, &mut rng);
+        // A one-row maze is a single corridor, with a dead end at each end.
+        assert_eq!(
+            hex_dead_ends(&maze),
+            vec![
+                HexCoordinates::from_offset(0, 0),
+                HexCoordinates::from_offset(2, 0)
+            ]
+        );
+    }
+}
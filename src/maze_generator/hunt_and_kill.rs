@@ -0,0 +1,111 @@
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{random_coordinates, MazeAlgorithm};
+
+/// Carves a maze with the Hunt-and-Kill algorithm: a random walk carves passages into unvisited
+/// cells until it gets stuck, then the grid is scanned for an unvisited cell next to a visited
+/// one, which is joined to the maze and becomes the start of the next walk.
+///
+/// Unlike [RecursiveBacktracker](super::RecursiveBacktracker), it needs no memory beyond the
+/// maze itself, however long its paths are.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct HuntAndKill;
+
+impl MazeAlgorithm for HuntAndKill {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        // A cell has been visited once it is populated, so no separate bookkeeping is needed.
+        let start = random_coordinates(maze, rng);
+        maze.set_cell(maze.new_cell(start));
+        let mut current = Some(start);
+        // Every row below this one, counting through the levels from the lowest, has been
+        // fully visited.
+        let mut hunt_line = 0;
+        while let Some(coordinates) = current {
+            let unvisited = neighbors(maze, &coordinates, false);
+            current = if unvisited.is_empty() {
+                hunt(maze, &mut hunt_line, rng)
+            } else {
+                let direction = unvisited[rng.gen_range(0..unvisited.len() as u32) as usize];
+                maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+                Some(coordinates + direction.coordinates())
+            };
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Returns the directions of the active neighbors of the cell at the given coordinates
+/// that have (or have not) been visited.
+fn neighbors(maze: &Maze, coordinates: &Coordinates, visited: bool) -> Vec<Direction> {
+    maze.directions()
+        .iter()
+        .copied()
+        .filter(|direction| {
+            let neighbor_coordinates = *coordinates + direction.coordinates();
+            maze.is_active(&neighbor_coordinates)
+                && maze.cell(&neighbor_coordinates).is_some() == visited
+        })
+        .collect()
+}
+
+/// Scans the grid for the first unvisited cell next to a visited one, joins it to a random
+/// visited neighbor, and returns its coordinates. Returns None if every cell has been visited.
+fn hunt(maze: &mut Maze, hunt_line: &mut i32, rng: &mut dyn RngCore) -> Option<Coordinates> {
+    let rows = maze.rows() as i32;
+    let mut first_unvisited_line = None;
+    for line in *hunt_line..(maze.levels() * maze.rows()) as i32 {
+        for column in 0..maze.columns() as i32 {
+            let coordinates = Coordinates::with_level(column, line % rows, line / rows);
+            if !maze.is_active(&coordinates) || maze.cell(&coordinates).is_some() {
+                continue;
+            }
+            let first_unvisited_line = *first_unvisited_line.get_or_insert(line);
+            let visited = neighbors(maze, &coordinates, true);
+            if !visited.is_empty() {
+                let direction = visited[rng.gen_range(0..visited.len() as u32) as usize];
+                maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+                *hunt_line = first_unvisited_line;
+                return Some(coordinates);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let mut maze = Maze::new(20, 15);
+        HuntAndKill.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_perfect_maze_for_many_seeds() {
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut maze = Maze::new(7, 5);
+            HuntAndKill.carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn carves_single_cell_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let mut maze = Maze::new(1, 1);
+        HuntAndKill.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+}
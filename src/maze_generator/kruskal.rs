@@ -0,0 +1,125 @@
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::disjoint_set::DisjointSet;
+use crate::maze_generator::MazeAlgorithm;
+
+/// Carves a maze with randomized Kruskal's algorithm: every interior edge is visited in a
+/// random order, and becomes a passage if the cells on either side are not yet connected.
+/// Produces short dead ends and dense branching.
+///
+/// Edges that are already assigned are left alone: passages count as already connecting
+/// their cells, tunnels as connecting the cells on either side of their crossing, and walls
+/// are never carved. This lets Kruskal fill in a maze around pre-carved passages, walls,
+/// rooms and crossings.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Kruskal;
+
+impl MazeAlgorithm for Kruskal {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut sets = DisjointSet::new(maze.grid_size());
+        // Each interior edge is listed once, from the cell on its south or west side, or on the
+        // level below it.
+        let mut edges: Vec<(Coordinates, Direction)> = Vec::new();
+        for coordinates in maze.coordinates() {
+            if !maze.is_active(&coordinates) {
+                continue;
+            }
+            for direction in [Direction::North, Direction::East, Direction::Above] {
+                let neighbor_coordinates = coordinates + direction.coordinates();
+                if !maze.is_active(&neighbor_coordinates) {
+                    continue;
+                }
+                let cell = maze.cell(&coordinates);
+                match cell.and_then(|cell| cell.edge(&direction)) {
+                    None => edges.push((coordinates, direction)),
+                    Some(CellEdge::Passage) => {
+                        sets.union(
+                            maze.get_index(&coordinates),
+                            maze.get_index(&neighbor_coordinates),
+                        );
+                    }
+                    Some(CellEdge::Tunnel) if !cell.unwrap().is_crossing() => {
+                        sets.union(
+                            maze.get_index(&coordinates),
+                            maze.get_index(&(neighbor_coordinates + direction.coordinates())),
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        edges.shuffle(rng);
+
+        for (coordinates, direction) in edges {
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            let edge = if sets.union(
+                maze.get_index(&coordinates),
+                maze.get_index(&neighbor_coordinates),
+            ) {
+                CellEdge::Passage
+            } else {
+                CellEdge::Wall
+            };
+            maze.set_edge(&coordinates, &direction, edge);
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let mut maze = Maze::new(23, 14);
+        Kruskal.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_row_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let mut maze = Maze::new(9, 1);
+        Kruskal.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn keeps_assigned_edges() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let mut maze = Maze::new(6, 6);
+        let wall = (Coordinates::new(2, 2), Direction::North);
+        let passage = (Coordinates::new(4, 1), Direction::East);
+        maze.set_edge(&wall.0, &wall.1, CellEdge::Wall);
+        maze.set_edge(&passage.0, &passage.1, CellEdge::Passage);
+        Kruskal.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+        assert_eq!(
+            maze.cell(&wall.0).unwrap().edge(&wall.1),
+            Some(CellEdge::Wall)
+        );
+        assert_eq!(
+            maze.cell(&passage.0).unwrap().edge(&passage.1),
+            Some(CellEdge::Passage)
+        );
+    }
+
+    #[test]
+    fn same_seed_carves_same_maze() {
+        let mut maze1 = Maze::new(10, 10);
+        let mut maze2 = Maze::new(10, 10);
+        Kruskal.carve(&mut maze1, &mut ChaCha8Rng::seed_from_u64(4));
+        Kruskal.carve(&mut maze2, &mut ChaCha8Rng::seed_from_u64(4));
+        assert_eq!(maze1, maze2);
+    }
+}
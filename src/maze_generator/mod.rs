@@ -0,0 +1,411 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::hex::HexMaze;
+use crate::maze::mask::Mask;
+use crate::maze::Maze;
+
+pub use aldous_broder::{AldousBroder, AldousBroderWilson};
+pub use bias::Bias;
+pub use binary_tree::{BinaryTree, Sidewinder};
+pub use braid::{braid, dead_ends};
+pub use chunked::ChunkedMaze;
+pub use constraints::{ConstraintError, Constraints, Room};
+pub use difficulty::{Difficulty, Metrics};
+pub use dungeon::Dungeon;
+pub use eller::{Eller, EllerRows};
+pub use growing_tree::{GenerationEvent, GenerationSteps, GrowingTree, Selection};
+pub use hex::{hex_dead_ends, HexMazeAlgorithm};
+pub use hunt_and_kill::HuntAndKill;
+pub use kruskal::Kruskal;
+pub use openings::{place_openings, OpeningStrategy};
+pub use prim::{SimplifiedPrim, TruePrim};
+pub use recursive_backtracker::RecursiveBacktracker;
+pub use recursive_division::RecursiveDivision;
+pub use tiled::Tiled;
+pub use weave::Weave;
+pub use wilson::Wilson;
+
+pub mod aldous_broder;
+pub mod bias;
+pub mod binary_tree;
+pub mod braid;
+pub mod chunked;
+pub mod constraints;
+pub mod difficulty;
+mod disjoint_set;
+pub mod dungeon;
+pub mod eller;
+pub mod growing_tree;
+pub mod hex;
+pub mod hunt_and_kill;
+pub mod kruskal;
+pub mod openings;
+pub mod prim;
+pub mod recursive_backtracker;
+pub mod recursive_division;
+pub mod tiled;
+pub mod weave;
+pub mod wilson;
+
+#[cfg(test)]
+mod test_support;
+
+/// An algorithm that carves an unpopulated [Maze] into a solvable maze.
+pub trait MazeAlgorithm {
+    /// Populates the given [Maze] with fully assigned cells, using the given random
+    /// number generator for every random choice.
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore);
+}
+
+/// Returns the algorithm with the given name, or None if there is no such algorithm.
+///
+/// The names are "recursive-backtracker", "kruskal", "prim", "simplified-prim", "wilson",
+/// "aldous-broder", "aldous-broder-wilson", "eller", "growing-tree" (which selects the
+/// newest cell 75% of the time and a random cell otherwise), "recursive-division",
+/// "binary-tree", "sidewinder", "hunt-and-kill", "dungeon" and "weave".
+pub fn algorithm(name: &str) -> Option<Box<dyn MazeAlgorithm>> {
+    match name {
+        "recursive-backtracker" => Some(Box::new(RecursiveBacktracker)),
+        "kruskal" => Some(Box::new(Kruskal)),
+        "prim" => Some(Box::new(TruePrim)),
+        "simplified-prim" => Some(Box::new(SimplifiedPrim)),
+        "wilson" => Some(Box::new(Wilson)),
+        "aldous-broder" => Some(Box::new(AldousBroder)),
+        "aldous-broder-wilson" => Some(Box::new(AldousBroderWilson::default())),
+        "eller" => Some(Box::new(Eller)),
+        "growing-tree" => Some(Box::new(GrowingTree::new(Selection::Weighted(vec![
+            (Selection::Newest, 3),
+            (Selection::Random, 1),
+        ])))),
+        "recursive-division" => Some(Box::new(RecursiveDivision::new())),
+        "binary-tree" => Some(Box::new(BinaryTree::default())),
+        "sidewinder" => Some(Box::new(Sidewinder::default())),
+        "hunt-and-kill" => Some(Box::new(HuntAndKill)),
+        "dungeon" => Some(Box::new(Dungeon::new())),
+        "weave" => Some(Box::new(Weave::default())),
+        _ => None,
+    }
+}
+
+/// Returns a [Maze] of a given size, fully populated with fully assigned cells and
+/// representing a solvable maze.
+pub fn generate(columns: u32, rows: u32) -> Maze {
+    generate_with_rng(columns, rows, &mut rand::thread_rng())
+}
+
+/// Returns a [Maze] of a given size, generated from the given seed.
+///
+/// The same seed always produces the same maze, on every platform.
+pub fn generate_with_seed(columns: u32, rows: u32, seed: u64) -> Maze {
+    generate_with_rng(columns, rows, &mut ChaCha8Rng::seed_from_u64(seed))
+}
+
+/// Returns a [Maze] of a given size, generated from the given seed in parallel tiles. The
+/// same seed always produces the same maze, however many threads there are. See [Tiled].
+pub fn generate_tiled(columns: u32, rows: u32, seed: u64) -> Maze {
+    Tiled::new().generate(columns, rows, seed)
+}
+
+/// Returns a [Maze] of a given size, using the given random number generator
+/// for every random choice.
+pub fn generate_with_rng<R: Rng + ?Sized>(columns: u32, rows: u32, mut rng: &mut R) -> Maze {
+    let mut maze = Maze::new(columns, rows);
+    RecursiveBacktracker.carve(&mut maze, &mut rng);
+    maze
+}
+
+/// Returns a [Maze] of a given size with the given number of levels, joined by stairs.
+pub fn generate_with_levels(columns: u32, rows: u32, levels: u32) -> Maze {
+    let mut maze = Maze::with_levels(columns, rows, levels);
+    RecursiveBacktracker.carve(&mut maze, &mut rand::thread_rng());
+    maze
+}
+
+/// Returns a [HexMaze] of a given size, fully populated with fully assigned cells and
+/// representing a perfect maze.
+pub fn generate_hex(columns: u32, rows: u32) -> HexMaze {
+    generate_hex_with(
+        &RecursiveBacktracker,
+        columns,
+        rows,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// Returns a [HexMaze] of a given size, generated from the given seed.
+///
+/// The same seed always produces the same maze, on every platform.
+pub fn generate_hex_with_seed(columns: u32, rows: u32, seed: u64) -> HexMaze {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    generate_hex_with(&RecursiveBacktracker, columns, rows, &mut rng)
+}
+
+/// Returns a [HexMaze] of a given size, carved by the given algorithm using the given random
+/// number generator for every random choice.
+pub fn generate_hex_with<A: HexMazeAlgorithm + ?Sized, R: Rng + ?Sized>(
+    algorithm: &A,
+    columns: u32,
+    rows: u32,
+    mut rng: &mut R,
+) -> HexMaze {
+    let mut maze = HexMaze::new(columns, rows);
+    algorithm.carve_hex(&mut maze, &mut rng);
+    maze
+}
+
+/// Returns a [Maze] with the size and shape of the given mask, in which only the mask's
+/// active cells are carved. The mask's active cells must be connected.
+pub fn generate_with_mask(mask: Mask) -> Maze {
+    let mut maze = Maze::with_mask(mask);
+    RecursiveBacktracker.carve(&mut maze, &mut rand::thread_rng());
+    maze
+}
+
+/// Returns a [Maze] of a given size, carved around the given pinned passages, walls and
+/// rooms, or an error if they can't be satisfied. See [Constraints::carve].
+pub fn generate_with_constraints(
+    columns: u32,
+    rows: u32,
+    constraints: &Constraints,
+) -> Result<Maze, ConstraintError> {
+    let mut maze = Maze::new(columns, rows);
+    constraints.carve(&mut maze, &mut rand::thread_rng())?;
+    Ok(maze)
+}
+
+/// Returns a [Maze] of a given size, with an entrance and exit, that is as close as the
+/// search can find to the given difficulty, with its metrics. The maze may not meet the
+/// difficulty; see [Difficulty::generate].
+pub fn generate_with_difficulty(columns: u32, rows: u32, target: &Difficulty) -> (Maze, Metrics) {
+    target.generate(columns, rows, &mut rand::thread_rng())
+}
+
+/// Returns a [Maze] of a given size, carved by the given algorithm.
+pub fn generate_with<A: MazeAlgorithm + ?Sized>(algorithm: &A, columns: u32, rows: u32) -> Maze {
+    let mut maze = Maze::new(columns, rows);
+    algorithm.carve(&mut maze, &mut rand::thread_rng());
+    maze
+}
+
+/// Carves a maze with more than one level with an algorithm that only carves flat mazes:
+/// each level is carved on its own as a flat maze, and then each pair of neighboring levels
+/// is joined by one staircase at a random cell. If the algorithm carves perfect mazes, so
+/// does this.
+pub fn carve_by_level<A: MazeAlgorithm + ?Sized>(
+    algorithm: &A,
+    maze: &mut Maze,
+    rng: &mut dyn RngCore,
+) {
+    for level in 0..maze.levels() as i32 {
+        let mut flat = Maze::new(maze.columns(), maze.rows());
+        algorithm.carve(&mut flat, rng);
+        for flat_cell in flat.into_iter().flatten() {
+            let flat_coordinates = flat_cell.coordinates();
+            let coordinates =
+                Coordinates::with_level(flat_coordinates.column(), flat_coordinates.row(), level);
+            let mut cell = maze.new_cell(coordinates);
+            cell.set_crossing(flat_cell.is_crossing());
+            for direction in Direction::ALL {
+                cell.set_edge(&direction, flat_cell.edge(&direction));
+            }
+            maze.set_cell(cell);
+        }
+    }
+    for level in 1..maze.levels() as i32 {
+        let column = rng.gen_range(0..maze.columns()) as i32;
+        let row = rng.gen_range(0..maze.rows()) as i32;
+        let coordinates = Coordinates::with_level(column, row, level);
+        maze.set_edge(&coordinates, &Direction::Below, CellEdge::Passage);
+    }
+    maze.close_unassigned_edges();
+}
+
+/// Returns the coordinates of a random active cell in the maze.
+pub(crate) fn random_coordinates<R: Rng + ?Sized>(maze: &Maze, rng: &mut R) -> Coordinates {
+    loop {
+        let column = rng.gen_range(0..maze.columns());
+        let row = rng.gen_range(0..maze.rows());
+        let level = random_level(maze, rng);
+        let coordinates = Coordinates::with_level(column as i32, row as i32, level);
+        if maze.is_active(&coordinates) {
+            return coordinates;
+        }
+    }
+}
+
+/// Returns a random level of the maze. Flat mazes don't draw a level, so that their seeds
+/// keep producing the same mazes as before mazes had levels.
+pub(crate) fn random_level<R: Rng + ?Sized>(maze: &Maze, rng: &mut R) -> i32 {
+    if maze.levels() > 1 {
+        rng.gen_range(0..maze.levels()) as i32
+    } else {
+        0
+    }
+}
+
+/// Returns a random active neighbor of the cell at the given coordinates, with the
+/// direction of the neighbor.
+pub(crate) fn random_neighbor<R: Rng + ?Sized>(
+    maze: &Maze,
+    coordinates: &Coordinates,
+    rng: &mut R,
+) -> (Direction, Coordinates) {
+    let neighbors: Vec<(Direction, Coordinates)> = maze
+        .directions()
+        .iter()
+        .map(|direction| (*direction, *coordinates + direction.coordinates()))
+        .filter(|(_direction, neighbor_coordinates)| maze.is_active(neighbor_coordinates))
+        .collect();
+    neighbors[rng.gen_range(0..neighbors.len() as u32) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_maze_is_perfect() {
+        test_support::assert_perfect(&generate(12, 8));
+    }
+
+    #[test]
+    fn same_seed_generates_same_maze() {
+        assert_eq!(
+            generate_with_seed(20, 10, 42),
+            generate_with_seed(20, 10, 42)
+        );
+    }
+
+    #[test]
+    fn different_seeds_generate_different_mazes() {
+        assert_ne!(generate_with_seed(20, 10, 1), generate_with_seed(20, 10, 2));
+    }
+
+    #[test]
+    fn same_rng_state_generates_same_maze() {
+        let mut rng1 = ChaCha8Rng::seed_from_u64(99);
+        let mut rng2 = ChaCha8Rng::seed_from_u64(99);
+        assert_eq!(
+            generate_with_rng(15, 15, &mut rng1),
+            generate_with_rng(15, 15, &mut rng2)
+        );
+    }
+
+    #[test]
+    fn algorithm_by_name() {
+        let algorithm = algorithm("simplified-prim").unwrap();
+        test_support::assert_perfect(&generate_with(algorithm.as_ref(), 9, 6));
+    }
+
+    #[test]
+    fn algorithm_by_unknown_name() {
+        assert!(algorithm("bogus").is_none());
+    }
+
+    #[test]
+    fn generate_with_mask_carves_only_active_cells() {
+        test_support::assert_perfect(&generate_with_mask(test_support::ring_mask()));
+    }
+
+    #[test]
+    fn algorithms_carve_perfect_masked_mazes() {
+        for name in [
+            "recursive-backtracker",
+            "kruskal",
+            "prim",
+            "simplified-prim",
+            "wilson",
+            "aldous-broder",
+            "aldous-broder-wilson",
+            "growing-tree",
+            "hunt-and-kill",
+        ] {
+            let mut maze = Maze::with_mask(test_support::ring_mask());
+            let mut rng = ChaCha8Rng::seed_from_u64(17);
+            algorithm(name).unwrap().carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+        }
+    }
+
+    #[test]
+    fn algorithms_carve_perfect_multilevel_mazes() {
+        for name in [
+            "recursive-backtracker",
+            "kruskal",
+            "prim",
+            "simplified-prim",
+            "wilson",
+            "aldous-broder",
+            "aldous-broder-wilson",
+            "growing-tree",
+            "hunt-and-kill",
+        ] {
+            let mut maze = Maze::with_levels(6, 5, 3);
+            let mut rng = ChaCha8Rng::seed_from_u64(24);
+            algorithm(name).unwrap().carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+            let stairs = maze
+                .into_iter()
+                .flatten()
+                .filter(|cell| cell.edge(&Direction::Above) == Some(CellEdge::Passage))
+                .count();
+            assert!(stairs > 0, "{name} carved no stairs");
+        }
+    }
+
+    #[test]
+    fn row_based_algorithms_carve_multilevel_mazes_by_level() {
+        for name in ["eller", "recursive-division", "binary-tree", "sidewinder"] {
+            let mut maze = Maze::with_levels(5, 4, 3);
+            let mut rng = ChaCha8Rng::seed_from_u64(24);
+            algorithm(name).unwrap().carve(&mut maze, &mut rng);
+            test_support::assert_perfect(&maze);
+            let stairs = maze
+                .into_iter()
+                .flatten()
+                .filter(|cell| cell.edge(&Direction::Above) == Some(CellEdge::Passage))
+                .count();
+            assert_eq!(stairs, 2, "{name}");
+        }
+    }
+
+    #[test]
+    fn tiled_carves_multilevel_mazes_by_level() {
+        let mut maze = Maze::with_levels(9, 7, 2);
+        Tiled::with_algorithm(Kruskal, 4).carve(&mut maze, &mut ChaCha8Rng::seed_from_u64(24));
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn row_based_algorithms_reject_masked_mazes() {
+        for name in ["eller", "recursive-division", "binary-tree", "sidewinder"] {
+            let result = std::panic::catch_unwind(|| {
+                let mut maze = Maze::with_mask(test_support::ring_mask());
+                let mut rng = ChaCha8Rng::seed_from_u64(17);
+                algorithm(name).unwrap().carve(&mut maze, &mut rng);
+            });
+            assert!(result.is_err(), "{name} carved a masked maze");
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_hex_maze() {
+        let maze = generate_hex_with_seed(12, 8, 42);
+        assert_eq!(maze, generate_hex_with_seed(12, 8, 42));
+        assert_ne!(maze, generate_hex_with_seed(12, 8, 43));
+        assert!(maze
+            .into_iter()
+            .flatten()
+            .all(|cell| cell.is_fully_assigned()));
+    }
+
+    #[test]
+    fn generate_with_boxed_algorithm() {
+        let algorithm: Box<dyn MazeAlgorithm> = Box::new(RecursiveBacktracker);
+        test_support::assert_perfect(&generate_with(algorithm.as_ref(), 9, 6));
+    }
+}
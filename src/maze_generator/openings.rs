@@ -0,0 +1,286 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_solver;
+
+/// Enumerates the ways [place_openings] can choose where a maze's entrance and exit go.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OpeningStrategy {
+    /// On opposite sides of the maze, either west and east or south and north.
+    OppositeSides,
+    /// At the two border cells with the longest path between them.
+    FarthestApart,
+    /// At two different random places on the border.
+    Random,
+}
+
+/// Adds an entrance and an exit to the border of a carved maze, placed according to the
+/// given strategy, records them as the maze's entrance and exit, and returns their coordinates
+/// and directions, entrance first.
+///
+/// The maze must have at least two border edges that face the outside of the maze. For a
+/// masked maze, that includes the edges facing masked-out cells that join up with the edge of
+/// the grid, but not those facing a hole enclosed by the maze.
+pub fn place_openings<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    strategy: OpeningStrategy,
+    rng: &mut R,
+) -> [(Coordinates, Direction); 2] {
+    let borders = border_edges(maze);
+    assert!(borders.len() >= 2, "Maze does not have two border edges");
+    let openings = match strategy {
+        OpeningStrategy::OppositeSides => {
+            let (entrance_side, exit_side) = if rng.gen_bool(0.5) {
+                (Direction::West, Direction::East)
+            } else {
+                (Direction::South, Direction::North)
+            };
+            [
+                outermost_edge(&borders, entrance_side, rng),
+                outermost_edge(&borders, exit_side, rng),
+            ]
+        }
+        OpeningStrategy::FarthestApart => {
+            // In a perfect maze, the farthest border cell from any border cell is at one end
+            // of the longest path between border cells, and the farthest from that is at the
+            // other end.
+            let from = farthest_border_edge(maze, &borders, &borders[0]);
+            let to = farthest_border_edge(maze, &borders, &from);
+            [from, to]
+        }
+        OpeningStrategy::Random => {
+            let mut chosen = borders.choose_multiple(rng, 2);
+            [*chosen.next().unwrap(), *chosen.next().unwrap()]
+        }
+    };
+    let [(entrance, entrance_side), (exit, exit_side)] = openings;
+    maze.set_entrance(&entrance, &entrance_side);
+    maze.set_exit(&exit, &exit_side);
+    openings
+}
+
+/// Returns the coordinates and direction of every border edge in the maze that faces the
+/// outside, in row order.
+fn border_edges(maze: &Maze) -> Vec<(Coordinates, Direction)> {
+    let outside = outside_cells(maze);
+    let faces_outside = |coordinates: Coordinates| {
+        !maze.in_bounds(&coordinates) || outside[maze.get_index(&coordinates)]
+    };
+    let mut borders = Vec::new();
+    for cell in maze.into_iter().flatten() {
+        for direction in Direction::ALL {
+            if cell.edge(&direction) == Some(CellEdge::Border)
+                && faces_outside(cell.coordinates() + direction.coordinates())
+            {
+                borders.push((cell.coordinates(), direction));
+            }
+        }
+    }
+    borders
+}
+
+/// Returns a vector, indexed like the maze, that is true for each masked-out location that
+/// can be reached from beyond the grid through other masked-out locations on its level.
+fn outside_cells(maze: &Maze) -> Vec<bool> {
+    let mut outside = vec![false; maze.grid_size()];
+    let mut stack: Vec<Coordinates> = maze
+        .coordinates()
+        .filter(|coordinates| {
+            !maze.is_active(coordinates)
+                && Direction::ALL
+                    .iter()
+                    .any(|direction| !maze.in_bounds(&(*coordinates + direction.coordinates())))
+        })
+        .collect();
+    for coordinates in &stack {
+        outside[maze.get_index(coordinates)] = true;
+    }
+    while let Some(coordinates) = stack.pop() {
+        for direction in Direction::ALL {
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            if maze.in_bounds(&neighbor_coordinates)
+                && !maze.is_active(&neighbor_coordinates)
+                && !outside[maze.get_index(&neighbor_coordinates)]
+            {
+                outside[maze.get_index(&neighbor_coordinates)] = true;
+                stack.push(neighbor_coordinates);
+            }
+        }
+    }
+    outside
+}
+
+/// Returns a random border edge facing the given side, from among the cells farthest
+/// towards that side.
+fn outermost_edge<R: Rng + ?Sized>(
+    borders: &[(Coordinates, Direction)],
+    side: Direction,
+    rng: &mut R,
+) -> (Coordinates, Direction) {
+    let extent = |coordinates: &Coordinates| match side {
+        Direction::North => coordinates.row(),
+        Direction::East => coordinates.column(),
+        Direction::South => -coordinates.row(),
+        Direction::West => -coordinates.column(),
+        Direction::Above => coordinates.level(),
+        Direction::Below => -coordinates.level(),
+    };
+    let facing: Vec<&(Coordinates, Direction)> = borders
+        .iter()
+        .filter(|(_coordinates, direction)| *direction == side)
+        .collect();
+    let outermost = facing
+        .iter()
+        .map(|(coordinates, _direction)| extent(coordinates))
+        .max()
+        .expect("every maze has a border on each side");
+    let candidates: Vec<&(Coordinates, Direction)> = facing
+        .into_iter()
+        .filter(|(coordinates, _direction)| extent(coordinates) == outermost)
+        .collect();
+    **candidates.choose(rng).unwrap()
+}
+
+/// Returns the border edge whose cell is farthest from the given border edge's cell, other
+/// than the given edge itself.
+fn farthest_border_edge(
+    maze: &Maze,
+    borders: &[(Coordinates, Direction)],
+    from: &(Coordinates, Direction),
+) -> (Coordinates, Direction) {
+    let distances = maze_solver::distances(maze, &from.0);
+    *borders
+        .iter()
+        .filter(|edge| *edge != from)
+        .max_by_key(|(coordinates, _direction)| distances.distance(coordinates))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use crate::maze_generator::{generate_with_mask, generate_with_seed};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn opposite_sides() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..10 {
+            let mut maze = generate_with_seed(8, 6, 0);
+            let [(entrance, entrance_side), (exit, exit_side)] =
+                place_openings(&mut maze, OpeningStrategy::OppositeSides, &mut rng);
+            assert_eq!(entrance_side.opposite(), exit_side);
+            match entrance_side {
+                Direction::West => assert_eq!((entrance.column(), exit.column()), (0, 7)),
+                Direction::South => assert_eq!((entrance.row(), exit.row()), (0, 5)),
+                _ => panic!("Unexpected entrance side {entrance_side:?}"),
+            }
+            test_support::assert_consistent(&maze);
+        }
+    }
+
+    #[test]
+    fn solves_from_entrance_when_exit_comes_first_in_row_order() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut checked = 0;
+        for _ in 0..50 {
+            let mut maze = generate_with_seed(8, 6, 0);
+            let [(entrance, _), (exit, _)] =
+                place_openings(&mut maze, OpeningStrategy::OppositeSides, &mut rng);
+            assert_eq!(
+                maze.entrance().map(|(coordinates, _)| coordinates),
+                Some(entrance)
+            );
+            assert_eq!(maze.exit().map(|(coordinates, _)| coordinates), Some(exit));
+            if entrance.row() > exit.row() {
+                let path = maze_solver::solve_openings(&maze).unwrap();
+                assert_eq!(path.first(), Some(&entrance));
+                assert_eq!(path.last(), Some(&exit));
+                checked += 1;
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn farthest_apart_is_longest_border_path() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut maze = generate_with_seed(10, 7, 4);
+        let borders = border_edges(&maze);
+        let longest = borders
+            .iter()
+            .map(|(from, _direction)| {
+                let distances = maze_solver::distances(&maze, from);
+                borders
+                    .iter()
+                    .map(|(to, _direction)| distances.distance(to).unwrap())
+                    .max()
+                    .unwrap()
+            })
+            .max()
+            .unwrap();
+        place_openings(&mut maze, OpeningStrategy::FarthestApart, &mut rng);
+        let path = maze_solver::solve_openings(&maze).unwrap();
+        assert_eq!(path.len() as u32 - 1, longest);
+    }
+
+    #[test]
+    fn random_openings_are_different() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..10 {
+            let mut maze = generate_with_seed(2, 1, 0);
+            let [entrance, exit] = place_openings(&mut maze, OpeningStrategy::Random, &mut rng);
+            assert_ne!(entrance, exit);
+            assert_eq!(maze.openings().len(), 2);
+        }
+    }
+
+    #[test]
+    fn openings_in_masked_maze_are_solvable() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut maze = generate_with_mask(test_support::ring_mask());
+        place_openings(&mut maze, OpeningStrategy::OppositeSides, &mut rng);
+        assert!(maze_solver::solve_openings(&maze).is_some());
+    }
+
+    /// Returns true if the given edge of the ring mask faces its enclosed hole.
+    fn faces_hole((coordinates, direction): &(Coordinates, Direction)) -> bool {
+        let neighbor = *coordinates + direction.coordinates();
+        (4..8).contains(&neighbor.column()) && (3..7).contains(&neighbor.row())
+    }
+
+    #[test]
+    fn border_edges_skip_enclosed_hole() {
+        let maze = generate_with_mask(test_support::ring_mask());
+        let borders = border_edges(&maze);
+        assert!(!borders.iter().any(faces_hole));
+        // The notch joins up with the edge of the grid, so its edges face the outside.
+        assert!(borders.contains(&(Coordinates::new(1, 4), Direction::West)));
+    }
+
+    #[test]
+    fn random_openings_in_ring_mask_face_outside() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..20 {
+            let mut maze = generate_with_mask(test_support::ring_mask());
+            let openings = place_openings(&mut maze, OpeningStrategy::Random, &mut rng);
+            assert!(!openings.iter().any(faces_hole));
+            assert!(maze_solver::solve_openings(&maze).is_some());
+        }
+    }
+
+    #[test]
+    fn farthest_apart_openings_in_ring_mask_face_outside() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut maze = generate_with_mask(test_support::ring_mask());
+        let openings = place_openings(&mut maze, OpeningStrategy::FarthestApart, &mut rng);
+        assert!(!openings.iter().any(faces_hole));
+        assert!(maze_solver::solve_openings(&maze).is_some());
+    }
+}
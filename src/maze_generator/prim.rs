@@ -0,0 +1,167 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{random_coordinates, MazeAlgorithm};
+
+/// Carves a maze with "true" Prim's algorithm: every interior edge has a random weight, and
+/// the maze grows from a random cell by always carving the lightest edge on its frontier.
+/// Produces a radial, branchy texture.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TruePrim;
+
+impl MazeAlgorithm for TruePrim {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut in_maze = vec![false; maze.grid_size()];
+        let mut frontier = BinaryHeap::new();
+
+        let start = random_coordinates(maze, rng);
+        add_to_maze(maze, &start, &mut in_maze, &mut frontier, rng);
+        while let Some(Reverse((_weight, level, column, row, direction_index))) = frontier.pop() {
+            let coordinates = Coordinates::with_level(column, row, level);
+            let direction = Direction::ALL_LEVELS[direction_index];
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            if !in_maze[maze.get_index(&neighbor_coordinates)] {
+                maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+                add_to_maze(
+                    maze,
+                    &neighbor_coordinates,
+                    &mut in_maze,
+                    &mut frontier,
+                    rng,
+                );
+            }
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+/// A weighted frontier edge, as (weight, level, column, row, index into
+/// [Direction::ALL_LEVELS]).
+type WeightedEdge = Reverse<(u32, i32, i32, i32, usize)>;
+
+fn add_to_maze(
+    maze: &Maze,
+    coordinates: &Coordinates,
+    in_maze: &mut [bool],
+    frontier: &mut BinaryHeap<WeightedEdge>,
+    rng: &mut dyn RngCore,
+) {
+    in_maze[maze.get_index(coordinates)] = true;
+    for (direction_index, direction) in maze.directions().iter().enumerate() {
+        let neighbor_coordinates = *coordinates + direction.coordinates();
+        if maze.is_active(&neighbor_coordinates) && !in_maze[maze.get_index(&neighbor_coordinates)]
+        {
+            // Each edge is weighted as it joins the frontier; an edge can only join once.
+            frontier.push(Reverse((
+                rng.gen(),
+                coordinates.level(),
+                coordinates.column(),
+                coordinates.row(),
+                direction_index,
+            )));
+        }
+    }
+}
+
+/// Carves a maze with simplified Prim's algorithm: the maze grows from a random cell by
+/// repeatedly picking a random cell on its frontier and connecting it to a random
+/// neighbor that is already part of the maze.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SimplifiedPrim;
+
+impl MazeAlgorithm for SimplifiedPrim {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut in_maze = vec![false; maze.grid_size()];
+        let mut in_frontier = vec![false; in_maze.len()];
+        let mut frontier: Vec<Coordinates> = Vec::new();
+
+        let start = random_coordinates(maze, rng);
+        in_maze[maze.get_index(&start)] = true;
+        extend_frontier(maze, &start, &in_maze, &mut in_frontier, &mut frontier);
+        while !frontier.is_empty() {
+            let coordinates =
+                frontier.swap_remove(rng.gen_range(0..frontier.len() as u32) as usize);
+            let directions: Vec<Direction> = maze
+                .directions()
+                .iter()
+                .copied()
+                .filter(|direction| {
+                    let neighbor_coordinates = coordinates + direction.coordinates();
+                    maze.is_active(&neighbor_coordinates)
+                        && in_maze[maze.get_index(&neighbor_coordinates)]
+                })
+                .collect();
+            let direction = directions[rng.gen_range(0..directions.len() as u32) as usize];
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            in_maze[maze.get_index(&coordinates)] = true;
+            extend_frontier(
+                maze,
+                &coordinates,
+                &in_maze,
+                &mut in_frontier,
+                &mut frontier,
+            );
+        }
+        maze.close_unassigned_edges();
+    }
+}
+
+fn extend_frontier(
+    maze: &Maze,
+    coordinates: &Coordinates,
+    in_maze: &[bool],
+    in_frontier: &mut [bool],
+    frontier: &mut Vec<Coordinates>,
+) {
+    for direction in maze.directions() {
+        let neighbor_coordinates = *coordinates + direction.coordinates();
+        if maze.is_active(&neighbor_coordinates) {
+            let index = maze.get_index(&neighbor_coordinates);
+            if !in_maze[index] && !in_frontier[index] {
+                in_frontier[index] = true;
+                frontier.push(neighbor_coordinates);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn true_prim_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let mut maze = Maze::new(19, 13);
+        TruePrim.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn simplified_prim_carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let mut maze = Maze::new(19, 13);
+        SimplifiedPrim.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_cell_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let mut maze1 = Maze::new(1, 1);
+        let mut maze2 = Maze::new(1, 1);
+        TruePrim.carve(&mut maze1, &mut rng);
+        SimplifiedPrim.carve(&mut maze2, &mut rng);
+        test_support::assert_perfect(&maze1);
+        test_support::assert_perfect(&maze2);
+    }
+}
@@ -0,0 +1,50 @@
+use rand::{Rng, RngCore};
+
+use crate::maze::Maze;
+use crate::maze_generator::growing_tree::{GenerationSteps, GrowingTree, Selection};
+use crate::maze_generator::MazeAlgorithm;
+
+/// Carves a maze by walking randomly from a random starting cell, backtracking whenever the
+/// current cell has no unvisited neighbors. Produces long, winding corridors with few branches.
+///
+/// This is a [GrowingTree] that always selects the newest cell.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RecursiveBacktracker;
+
+impl RecursiveBacktracker {
+    /// Returns an iterator that carves the given unpopulated maze one event at a time.
+    /// See [GrowingTree::steps].
+    pub fn steps<R: Rng>(&self, maze: Maze, rng: R) -> GenerationSteps<R> {
+        GrowingTree::new(Selection::Newest).steps(maze, rng)
+    }
+}
+
+impl MazeAlgorithm for RecursiveBacktracker {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        GrowingTree::new(Selection::Newest).carve(maze, rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut maze = Maze::new(17, 11);
+        RecursiveBacktracker.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_cell_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut maze = Maze::new(1, 1);
+        RecursiveBacktracker.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+}
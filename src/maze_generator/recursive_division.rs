@@ -0,0 +1,192 @@
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{carve_by_level, MazeAlgorithm};
+
+/// Builds a maze by adding walls rather than carving passages: the maze starts as one open
+/// chamber, which is split in two by a wall with a single gap, and each half is split in turn.
+/// Produces long, straight walls and a rectangular look.
+///
+/// By default chambers are split down to single cells, which produces a perfect maze. With a
+/// larger room size, chambers that fit within it are left as open rooms. It cannot carve a
+/// masked maze, and carves levels with [carve_by_level].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RecursiveDivision {
+    room_size: u32,
+}
+
+impl RecursiveDivision {
+    /// Creates a new RecursiveDivision that splits chambers down to single cells.
+    pub fn new() -> RecursiveDivision {
+        RecursiveDivision { room_size: 1 }
+    }
+
+    /// Creates a new RecursiveDivision that stops splitting a chamber once neither its width
+    /// nor its height is greater than the given size, leaving it as an open room.
+    pub fn with_room_size(room_size: u32) -> RecursiveDivision {
+        assert!(room_size > 0, "Room size must be at least 1");
+        RecursiveDivision { room_size }
+    }
+
+    /// Returns the largest width and height of a chamber that is left undivided.
+    pub fn room_size(&self) -> u32 {
+        self.room_size
+    }
+}
+
+impl Default for RecursiveDivision {
+    fn default() -> RecursiveDivision {
+        RecursiveDivision::new()
+    }
+}
+
+/// A rectangular area of the maze that has walls on all sides, except for the gaps left
+/// when it was split from its parent.
+struct Chamber {
+    column: i32,
+    row: i32,
+    width: i32,
+    height: i32,
+}
+
+impl MazeAlgorithm for RecursiveDivision {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        assert!(
+            maze.mask().is_none(),
+            "RecursiveDivision cannot carve a masked maze"
+        );
+        if maze.levels() > 1 {
+            carve_by_level(self, maze, rng);
+            return;
+        }
+        open_field(maze);
+
+        let room_size = self.room_size as i32;
+        let mut chambers = vec![Chamber {
+            column: 0,
+            row: 0,
+            width: maze.columns() as i32,
+            height: maze.rows() as i32,
+        }];
+        while let Some(chamber) = chambers.pop() {
+            let split_horizontally = match (chamber.width > room_size, chamber.height > room_size) {
+                (false, false) => continue,
+                (true, false) => false,
+                (false, true) => true,
+                (true, true) => match chamber.height.cmp(&chamber.width) {
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Less => false,
+                    std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+                },
+            };
+
+            if split_horizontally {
+                // The wall runs along the north side of the row it is placed on.
+                let wall_row = chamber.row + rng.gen_range(0..chamber.height - 1);
+                let gap = chamber.column + rng.gen_range(0..chamber.width);
+                for column in chamber.column..chamber.column + chamber.width {
+                    if column != gap {
+                        let coordinates = Coordinates::new(column, wall_row);
+                        maze.set_edge(&coordinates, &Direction::North, CellEdge::Wall);
+                    }
+                }
+                let south_height = wall_row - chamber.row + 1;
+                chambers.push(Chamber {
+                    height: south_height,
+                    ..chamber
+                });
+                chambers.push(Chamber {
+                    row: wall_row + 1,
+                    height: chamber.height - south_height,
+                    ..chamber
+                });
+            } else {
+                // The wall runs along the east side of the column it is placed on.
+                let wall_column = chamber.column + rng.gen_range(0..chamber.width - 1);
+                let gap = chamber.row + rng.gen_range(0..chamber.height);
+                for row in chamber.row..chamber.row + chamber.height {
+                    if row != gap {
+                        let coordinates = Coordinates::new(wall_column, row);
+                        maze.set_edge(&coordinates, &Direction::East, CellEdge::Wall);
+                    }
+                }
+                let west_width = wall_column - chamber.column + 1;
+                chambers.push(Chamber {
+                    width: west_width,
+                    ..chamber
+                });
+                chambers.push(Chamber {
+                    column: wall_column + 1,
+                    width: chamber.width - west_width,
+                    ..chamber
+                });
+            }
+        }
+    }
+}
+
+/// Populates every cell of the maze with passages to all of its neighbors, and borders
+/// around the outside.
+fn open_field(maze: &mut Maze) {
+    for row in 0..maze.rows() as i32 {
+        for column in 0..maze.columns() as i32 {
+            let coordinates = Coordinates::new(column, row);
+            for direction in Direction::ALL {
+                if maze.in_bounds(&(coordinates + direction.coordinates())) {
+                    maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+                } else {
+                    maze.set_edge(&coordinates, &direction, CellEdge::Border);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut maze = Maze::new(21, 13);
+        RecursiveDivision::new().carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_row_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut maze = Maze::new(6, 1);
+        RecursiveDivision::new().carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn room_size_leaves_connected_open_rooms() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut maze = Maze::new(24, 16);
+        RecursiveDivision::with_room_size(4).carve(&mut maze, &mut rng);
+        test_support::assert_consistent(&maze);
+        let cells = maze.grid_size();
+        assert_eq!(
+            test_support::reachable_cells(&maze, Coordinates::new(0, 0)),
+            cells
+        );
+        assert!(test_support::passage_count(&maze) > cells - 1, "No rooms");
+    }
+
+    #[test]
+    fn room_as_large_as_maze_is_left_open() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut maze = Maze::new(3, 3);
+        RecursiveDivision::with_room_size(3).carve(&mut maze, &mut rng);
+        assert_eq!(test_support::passage_count(&maze), 12);
+    }
+}
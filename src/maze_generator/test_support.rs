@@ -0,0 +1,98 @@
+//! Assertions shared by the maze generator tests.
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::mask::Mask;
+use crate::maze::Maze;
+
+/// Asserts that every active cell is populated and fully assigned, that every masked-out
+/// cell is not, that both sides of every interior edge agree, and that the edges facing out
+/// of the maze are borders or openings.
+pub(crate) fn assert_consistent(maze: &Maze) {
+    for coordinates in maze.coordinates() {
+        if !maze.is_active(&coordinates) {
+            assert_eq!(maze.cell(&coordinates), None, "Masked cell populated");
+            continue;
+        }
+        let cell = maze
+            .cell(&coordinates)
+            .unwrap_or_else(|| panic!("Unpopulated cell at {coordinates:?}"));
+        assert!(cell.is_fully_assigned(), "Not fully assigned: {cell:?}");
+        for direction in Direction::ALL_LEVELS {
+            let edge = cell.edge(&direction);
+            let neighbor_coordinates = coordinates + direction.coordinates();
+            if maze.is_active(&neighbor_coordinates) {
+                let neighbor = maze.cell(&neighbor_coordinates).unwrap();
+                assert_ne!(edge, Some(CellEdge::Border), "Interior border at {cell:?}");
+                assert_eq!(
+                    edge,
+                    neighbor.edge(&direction.opposite()),
+                    "Mismatched edge between {cell:?} and {neighbor:?}"
+                );
+            } else {
+                assert!(
+                    matches!(edge, Some(CellEdge::Border | CellEdge::Opening)),
+                    "Missing border at {cell:?}"
+                );
+            }
+        }
+    }
+}
+
+/// Returns the number of cells reachable from the given coordinates through passages.
+pub(crate) fn reachable_cells(maze: &Maze, start: Coordinates) -> usize {
+    let mut visited = vec![false; maze.grid_size()];
+    let index = |coordinates: &Coordinates| maze.get_index(coordinates);
+    let mut stack = vec![start];
+    visited[index(&start)] = true;
+    let mut count = 0;
+    while let Some(coordinates) = stack.pop() {
+        count += 1;
+        let cell = maze.cell(&coordinates).unwrap();
+        for direction in Direction::ALL_LEVELS {
+            if cell.edge(&direction) == Some(CellEdge::Passage) {
+                let neighbor = coordinates + direction.coordinates();
+                if !visited[index(&neighbor)] {
+                    visited[index(&neighbor)] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Returns the number of interior passages in the maze.
+pub(crate) fn passage_count(maze: &Maze) -> usize {
+    let mut count = 0;
+    for cell in maze.into_iter().flatten() {
+        for direction in [Direction::North, Direction::East, Direction::Above] {
+            if cell.edge(&direction) == Some(CellEdge::Passage) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Asserts that the maze is consistent, and that it is perfect: every cell can be
+/// reached from every other cell by exactly one path.
+pub(crate) fn assert_perfect(maze: &Maze) {
+    assert_consistent(maze);
+    let cells = maze.active_count();
+    let start = maze.into_iter().flatten().next().unwrap().coordinates();
+    assert_eq!(reachable_cells(maze, start), cells, "Maze is not connected");
+    assert_eq!(passage_count(maze), cells - 1, "Maze has loops");
+}
+
+/// Returns a mask shaped like a ring with a notch cut into it, so that the maze has to be
+/// carved around a hole and into a dead-end pocket.
+pub(crate) fn ring_mask() -> Mask {
+    Mask::from_fn(12, 10, |coordinates| {
+        let (column, row) = (coordinates.column(), coordinates.row());
+        let in_hole = (4..8).contains(&column) && (3..7).contains(&row);
+        let in_notch = column == 0 && (2..8).contains(&row);
+        !in_hole && !in_notch
+    })
+}
@@ -0,0 +1,277 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::maze::cell::Cell;
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::disjoint_set::DisjointSet;
+use crate::maze_generator::{carve_by_level, MazeAlgorithm, RecursiveBacktracker};
+
+/// Carves a large maze in parallel: the maze is split into square tiles, each tile is carved
+/// by the given algorithm on its own thread, and then the tiles are stitched together with
+/// one passage across each of the seams on a random spanning tree of the tiles. The result
+/// is a perfect maze if the algorithm carves perfect mazes.
+///
+/// Each tile has its own random number generator, seeded from the seed and the tile's
+/// position, so a seed produces the same maze however many threads there are. Corridors
+/// only cross between tiles at the stitches, so the tiles show in the maze's texture. It
+/// carves levels with [carve_by_level].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tiled<A> {
+    algorithm: A,
+    tile_size: u32,
+}
+
+impl Tiled<RecursiveBacktracker> {
+    /// Creates a new Tiled that carves tiles of 256 by 256 cells with the
+    /// [RecursiveBacktracker].
+    pub fn new() -> Tiled<RecursiveBacktracker> {
+        Tiled::with_algorithm(RecursiveBacktracker, 256)
+    }
+}
+
+impl Default for Tiled<RecursiveBacktracker> {
+    fn default() -> Tiled<RecursiveBacktracker> {
+        Tiled::new()
+    }
+}
+
+impl<A: MazeAlgorithm + Sync> Tiled<A> {
+    /// Creates a new Tiled that carves tiles of the given size with the given algorithm.
+    pub fn with_algorithm(algorithm: A, tile_size: u32) -> Tiled<A> {
+        assert!(tile_size > 0, "Tile size must be at least 1");
+        Tiled {
+            algorithm,
+            tile_size,
+        }
+    }
+
+    /// Returns the width and height of a tile.
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// Returns a [Maze] of a given size, generated from the given seed.
+    pub fn generate(&self, columns: u32, rows: u32, seed: u64) -> Maze {
+        let mut maze = Maze::new(columns, rows);
+        self.carve_seeded(&mut maze, seed);
+        maze
+    }
+
+    fn carve_seeded(&self, maze: &mut Maze, seed: u64) {
+        assert!(maze.mask().is_none(), "Tiled cannot carve a masked maze");
+        assert_eq!(
+            maze.levels(),
+            1,
+            "Tiled cannot carve a maze with more than one level"
+        );
+        if maze.columns() == 0 || maze.rows() == 0 {
+            return;
+        }
+        let tile_columns = maze.columns().div_ceil(self.tile_size);
+        let tile_rows = maze.rows().div_ceil(self.tile_size);
+        let tile_count = (tile_columns * tile_rows) as usize;
+
+        // Split each row of the maze into the segments that fall in each tile, so that every
+        // tile can be carved and copied straight into its own part of the maze in parallel,
+        // without holding a second copy of the maze.
+        let (columns, rows) = (maze.columns(), maze.rows());
+        let bounds: Vec<(Coordinates, u32, u32)> = (0..tile_count)
+            .map(|index| self.tile_bounds(maze, tile_columns, index))
+            .collect();
+        let mut segments: Vec<Vec<&mut [Option<Cell>]>> =
+            (0..tile_count).map(|_index| Vec::new()).collect();
+        for (row, cells) in maze.cells_mut().chunks_mut(columns as usize).enumerate() {
+            let first_tile = row / self.tile_size as usize * tile_columns as usize;
+            let mut rest = cells;
+            for (index, tile_segments) in segments[first_tile..][..tile_columns as usize]
+                .iter_mut()
+                .enumerate()
+            {
+                let (_origin, tile_width, _rows) = bounds[first_tile + index];
+                let (segment, remainder) = rest.split_at_mut(tile_width as usize);
+                tile_segments.push(segment);
+                rest = remainder;
+            }
+        }
+
+        // Stream 0 is used for stitching, and each tile gets the stream after its index.
+        segments
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(index, tile_segments)| {
+                let (origin, tile_width, tile_height) = bounds[index];
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                rng.set_stream(index as u64 + 1);
+                let mut tile = Maze::new(tile_width, tile_height);
+                self.algorithm.carve(&mut tile, &mut rng);
+                copy_tile(&tile, origin, columns, rows, tile_segments);
+            });
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(0);
+        self.stitch(maze, tile_columns, tile_rows, &mut rng);
+    }
+
+    /// Returns the coordinates of the south-west cell of the tile with the given index, and
+    /// the tile's width and height, which are smaller than the tile size at the north and
+    /// east edges of the maze if the tile size doesn't divide the maze evenly.
+    fn tile_bounds(&self, maze: &Maze, tile_columns: u32, index: usize) -> (Coordinates, u32, u32) {
+        let column = index as u32 % tile_columns * self.tile_size;
+        let row = index as u32 / tile_columns * self.tile_size;
+        (
+            Coordinates::new(column as i32, row as i32),
+            self.tile_size.min(maze.columns() - column),
+            self.tile_size.min(maze.rows() - row),
+        )
+    }
+
+    /// Joins the tiles with one passage across each seam on a random spanning tree of the
+    /// tiles, found with Kruskal's algorithm.
+    fn stitch<R: Rng + ?Sized>(
+        &self,
+        maze: &mut Maze,
+        tile_columns: u32,
+        tile_rows: u32,
+        rng: &mut R,
+    ) {
+        // Each seam is listed once, from the tile on its south or west side.
+        let mut seams: Vec<(u32, u32, Direction)> = Vec::new();
+        for tile_row in 0..tile_rows {
+            for tile_column in 0..tile_columns {
+                if tile_row + 1 < tile_rows {
+                    seams.push((tile_column, tile_row, Direction::North));
+                }
+                if tile_column + 1 < tile_columns {
+                    seams.push((tile_column, tile_row, Direction::East));
+                }
+            }
+        }
+        seams.shuffle(rng);
+
+        let mut sets = DisjointSet::new((tile_columns * tile_rows) as usize);
+        for (tile_column, tile_row, direction) in seams {
+            let index = (tile_row * tile_columns + tile_column) as usize;
+            let neighbor_index = match direction {
+                Direction::North => index + tile_columns as usize,
+                _ => index + 1,
+            };
+            if !sets.union(index, neighbor_index) {
+                continue;
+            }
+            let (origin, columns, rows) = self.tile_bounds(maze, tile_columns, index);
+            let coordinates = match direction {
+                Direction::North => Coordinates::new(
+                    origin.column() + rng.gen_range(0..columns) as i32,
+                    origin.row() + rows as i32 - 1,
+                ),
+                _ => Coordinates::new(
+                    origin.column() + columns as i32 - 1,
+                    origin.row() + rng.gen_range(0..rows) as i32,
+                ),
+            };
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+        }
+    }
+}
+
+impl<A: MazeAlgorithm + Sync> MazeAlgorithm for Tiled<A> {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        if maze.levels() > 1 {
+            carve_by_level(self, maze, rng);
+            return;
+        }
+        self.carve_seeded(maze, rng.next_u64());
+    }
+}
+
+/// Copies the carved tile, with its south-west cell at the given origin, into the segments
+/// of the rows of a maze of the given size that it covers. The tile's borders become walls
+/// wherever they face another tile.
+fn copy_tile(
+    tile: &Maze,
+    origin: Coordinates,
+    columns: u32,
+    rows: u32,
+    segments: Vec<&mut [Option<Cell>]>,
+) {
+    let in_bounds = |coordinates: Coordinates| {
+        (0..columns).contains(&(coordinates.column() as u32))
+            && (0..rows).contains(&(coordinates.row() as u32))
+    };
+    let slots = segments.into_iter().flat_map(|segment| segment.iter_mut());
+    for (tile_cell, slot) in tile.into_iter().zip(slots) {
+        let Some(tile_cell) = tile_cell else {
+            continue;
+        };
+        let coordinates = tile_cell.coordinates() + origin;
+        let mut cell = Cell::new(coordinates);
+        cell.set_crossing(tile_cell.is_crossing());
+        for direction in Direction::ALL {
+            let edge = match tile_cell.edge(&direction) {
+                Some(CellEdge::Border) if in_bounds(coordinates + direction.coordinates()) => {
+                    Some(CellEdge::Wall)
+                }
+                edge => edge,
+            };
+            cell.set_edge(&direction, edge);
+        }
+        *slot = Some(cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::{test_support, Kruskal};
+
+    #[test]
+    fn carves_perfect_maze() {
+        test_support::assert_perfect(&Tiled::with_algorithm(Kruskal, 8).generate(30, 20, 21));
+    }
+
+    #[test]
+    fn single_tile_matches_algorithm() {
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        rng.set_stream(1);
+        let mut maze = Maze::new(12, 9);
+        RecursiveBacktracker.carve(&mut maze, &mut rng);
+        assert_eq!(Tiled::new().generate(12, 9, 21), maze);
+    }
+
+    #[test]
+    fn generates_empty_maze() {
+        assert_eq!(Tiled::new().generate(0, 5, 21), Maze::new(0, 5));
+        assert_eq!(Tiled::new().generate(5, 0, 21), Maze::new(5, 0));
+    }
+
+    #[test]
+    fn same_seed_generates_same_maze_on_any_number_of_threads() {
+        let tiled = Tiled::with_algorithm(RecursiveBacktracker, 7);
+        let generate = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| tiled.generate(40, 33, 5))
+        };
+        let maze = generate(1);
+        assert_eq!(generate(4), maze);
+        assert_eq!(tiled.generate(40, 33, 5), maze);
+        assert_ne!(tiled.generate(40, 33, 6), maze);
+    }
+
+    #[test]
+    fn rejects_masked_maze() {
+        let result = std::panic::catch_unwind(|| {
+            let mut maze = Maze::with_mask(test_support::ring_mask());
+            let mut rng = ChaCha8Rng::seed_from_u64(21);
+            Tiled::new().carve(&mut maze, &mut rng);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use rand::{Rng, RngCore};
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::disjoint_set::DisjointSet;
+use crate::maze_generator::{random_coordinates, Kruskal, MazeAlgorithm};
+
+/// Carves a weave maze, in which passages can tunnel under a perpendicular passage in a
+/// neighboring cell. Crossings are scattered across the maze first, and then the rest of the
+/// maze is carved around them with [Kruskal]'s algorithm. Produces a perfect maze, in which
+/// every cell can be reached from every other by exactly one path, counting each step
+/// through a tunnel as a single move. See [crate::maze_solver::moves].
+///
+/// A crossing needs a neighbor in every direction, so it is never placed at the border or
+/// next to a masked-out cell, and it is never placed next to another crossing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Weave {
+    density: f64,
+}
+
+impl Weave {
+    /// Creates a new Weave that tries to make the given fraction (between 0.0 and 1.0) of
+    /// the maze's cells into crossings. Attempts that don't fit are dropped, so the maze ends
+    /// up with fewer crossings than that.
+    pub fn new(density: f64) -> Weave {
+        assert!(
+            (0.0..=1.0).contains(&density),
+            "Density {density} is not between 0 and 1"
+        );
+        Weave { density }
+    }
+
+    /// Returns the fraction of the maze's cells that it tries to make into crossings.
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+}
+
+impl Default for Weave {
+    /// Creates a new Weave that tries to make a fifth of the maze's cells into crossings.
+    fn default() -> Weave {
+        Weave::new(0.2)
+    }
+}
+
+impl MazeAlgorithm for Weave {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut sets = DisjointSet::new(maze.grid_size());
+        let attempts = (maze.active_count() as f64 * self.density).round() as usize;
+        for _ in 0..attempts {
+            let coordinates = random_coordinates(maze, rng);
+            let (over, under) = if rng.gen_bool(0.5) {
+                (Direction::North, Direction::East)
+            } else {
+                (Direction::East, Direction::North)
+            };
+            add_crossing(maze, &mut sets, coordinates, over, under);
+        }
+        Kruskal.carve(maze, rng);
+    }
+}
+
+/// Makes the cell at the given coordinates into a crossing, with a passage running over it in
+/// the `over` direction and a tunnel running under it in the `under` direction, if it has a
+/// neighbor in every direction, none of its edges are assigned yet, and the passage and the
+/// tunnel would not close a loop.
+fn add_crossing(
+    maze: &mut Maze,
+    sets: &mut DisjointSet,
+    coordinates: Coordinates,
+    over: Direction,
+    under: Direction,
+) -> bool {
+    let neighbor = |direction: Direction| coordinates + direction.coordinates();
+    let fits = Direction::ALL
+        .into_iter()
+        .all(|direction| maze.is_active(&neighbor(direction)))
+        && match maze.cell(&coordinates) {
+            Some(cell) => cell.unassigned_directions().len() == maze.directions().len(),
+            None => true,
+        };
+    if !fits {
+        return false;
+    }
+
+    let index = |coordinates: Coordinates| maze.get_index(&coordinates);
+    let over_ends = [
+        sets.find(index(neighbor(over))),
+        sets.find(index(coordinates)),
+        sets.find(index(neighbor(over.opposite()))),
+    ];
+    let mut under_ends = [
+        sets.find(index(neighbor(under))),
+        sets.find(index(neighbor(under.opposite()))),
+    ];
+    // The over passage joins its three cells into one set, which the tunnel mustn't join
+    // to itself.
+    for end in under_ends.iter_mut() {
+        if over_ends.contains(end) {
+            *end = over_ends[0];
+        }
+    }
+    if over_ends[0] == over_ends[1]
+        || over_ends[1] == over_ends[2]
+        || over_ends[0] == over_ends[2]
+        || under_ends[0] == under_ends[1]
+    {
+        return false;
+    }
+
+    sets.union(index(neighbor(over)), index(coordinates));
+    sets.union(index(coordinates), index(neighbor(over.opposite())));
+    sets.union(index(neighbor(under)), index(neighbor(under.opposite())));
+    maze.set_edge(&coordinates, &over, CellEdge::Passage);
+    maze.set_edge(&coordinates, &over.opposite(), CellEdge::Passage);
+    maze.set_edge(&coordinates, &under, CellEdge::Tunnel);
+    maze.set_edge(&coordinates, &under.opposite(), CellEdge::Tunnel);
+    let mut cell = maze.cell(&coordinates).unwrap();
+    cell.set_crossing(true);
+    maze.set_cell(cell);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use crate::maze_solver;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Asserts that every cell can be reached from the first, and that there are exactly
+    /// enough moves between cells to connect them, so there are no loops.
+    fn assert_perfect_weave(maze: &Maze) {
+        test_support::assert_consistent(maze);
+        let cells: Vec<Coordinates> = maze
+            .into_iter()
+            .flatten()
+            .map(|c| c.coordinates())
+            .collect();
+        let distances = maze_solver::distances(maze, &cells[0]);
+        for coordinates in &cells {
+            assert!(distances.distance(coordinates).is_some(), "{coordinates:?}");
+        }
+        let moves: usize = cells
+            .iter()
+            .map(|coordinates| maze_solver::moves(maze, coordinates).len())
+            .sum();
+        assert_eq!(moves / 2, cells.len() - 1, "Maze has loops");
+    }
+
+    fn crossings(maze: &Maze) -> Vec<Coordinates> {
+        maze.into_iter()
+            .flatten()
+            .filter(|cell| cell.is_crossing())
+            .map(|cell| cell.coordinates())
+            .collect()
+    }
+
+    #[test]
+    fn carves_perfect_weave_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(23);
+        let mut maze = Maze::new(20, 15);
+        Weave::default().carve(&mut maze, &mut rng);
+        assert_perfect_weave(&maze);
+        assert!(!crossings(&maze).is_empty());
+    }
+
+    #[test]
+    fn carves_perfect_masked_weave_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(23);
+        let mut maze = Maze::with_mask(test_support::ring_mask());
+        Weave::new(0.5).carve(&mut maze, &mut rng);
+        assert_perfect_weave(&maze);
+    }
+
+    #[test]
+    fn crossings_have_a_passage_over_a_tunnel() {
+        let mut rng = ChaCha8Rng::seed_from_u64(23);
+        let mut maze = Maze::new(20, 15);
+        Weave::new(1.0).carve(&mut maze, &mut rng);
+        for coordinates in crossings(&maze) {
+            let cell = maze.cell(&coordinates).unwrap();
+            let (over, under) = if cell.edge(&Direction::North) == Some(CellEdge::Passage) {
+                (Direction::North, Direction::East)
+            } else {
+                (Direction::East, Direction::North)
+            };
+            assert_eq!(cell.edge(&over.opposite()), Some(CellEdge::Passage));
+            assert_eq!(cell.edge(&under), Some(CellEdge::Tunnel));
+            assert_eq!(cell.edge(&under.opposite()), Some(CellEdge::Tunnel));
+            for direction in Direction::ALL {
+                let neighbor = maze.cell(&(coordinates + direction.coordinates())).unwrap();
+                assert!(!neighbor.is_crossing());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_crossing_that_closes_a_loop() {
+        let mut maze = Maze::new(3, 3);
+        let middle = Coordinates::new(1, 1);
+        // With the cells west and east of the middle already joined, both the tunnel and the
+        // passage between them would close a loop.
+        let mut sets = DisjointSet::new(9);
+        sets.union(3, 5);
+        assert!(!add_crossing(
+            &mut maze,
+            &mut sets,
+            middle,
+            Direction::North,
+            Direction::East
+        ));
+        assert!(!add_crossing(
+            &mut maze,
+            &mut sets,
+            middle,
+            Direction::East,
+            Direction::North
+        ));
+        // Joining the cells west and north of the middle doesn't.
+        let mut sets = DisjointSet::new(9);
+        sets.union(3, 7);
+        assert!(add_crossing(
+            &mut maze,
+            &mut sets,
+            middle,
+            Direction::North,
+            Direction::East
+        ));
+        assert!(maze.cell(&middle).unwrap().is_crossing());
+    }
+
+    #[test]
+    fn crossing_needs_neighbors_on_every_side() {
+        let mut maze = Maze::new(3, 3);
+        let mut sets = DisjointSet::new(9);
+        let corner = Coordinates::new(0, 1);
+        assert!(!add_crossing(
+            &mut maze,
+            &mut sets,
+            corner,
+            Direction::North,
+            Direction::East
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+use rand::RngCore;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+use crate::maze_generator::{random_coordinates, random_neighbor, MazeAlgorithm};
+
+/// Carves a maze with Wilson's algorithm: starting from a single random cell, each remaining
+/// cell is joined to the maze by a loop-erased random walk. The result is sampled uniformly
+/// from every possible perfect maze of the given size, with no algorithmic bias.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Wilson;
+
+impl MazeAlgorithm for Wilson {
+    fn carve(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        let mut in_maze = vec![false; maze.grid_size()];
+        let start = random_coordinates(maze, rng);
+        in_maze[maze.get_index(&start)] = true;
+        add_remaining_cells(maze, &mut in_maze, rng);
+        maze.close_unassigned_edges();
+    }
+}
+
+/// Joins every cell that is not yet in the maze to it with loop-erased random walks, carving
+/// a passage along each walk. At least one cell must already be in the maze.
+pub(crate) fn add_remaining_cells(maze: &mut Maze, in_maze: &mut [bool], rng: &mut dyn RngCore) {
+    // The direction the walk most recently left each cell in. Overwriting it when the walk
+    // revisits a cell is what erases the loop.
+    let mut exits: Vec<Option<Direction>> = vec![None; in_maze.len()];
+    let all_coordinates: Vec<Coordinates> = maze.coordinates().collect();
+    for start in all_coordinates {
+        if !maze.is_active(&start) || in_maze[maze.get_index(&start)] {
+            continue;
+        }
+
+        let mut coordinates = start;
+        while !in_maze[maze.get_index(&coordinates)] {
+            let (direction, neighbor_coordinates) = random_neighbor(maze, &coordinates, rng);
+            exits[maze.get_index(&coordinates)] = Some(direction);
+            coordinates = neighbor_coordinates;
+        }
+
+        let mut coordinates = start;
+        while !in_maze[maze.get_index(&coordinates)] {
+            let index = maze.get_index(&coordinates);
+            let direction = exits[index].expect("walk left every cell on its path");
+            maze.set_edge(&coordinates, &direction, CellEdge::Passage);
+            in_maze[index] = true;
+            coordinates += direction.coordinates();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::test_support;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn carves_perfect_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        let mut maze = Maze::new(16, 12);
+        Wilson.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn carves_single_column_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        let mut maze = Maze::new(1, 7);
+        Wilson.carve(&mut maze, &mut rng);
+        test_support::assert_perfect(&maze);
+    }
+
+    #[test]
+    fn samples_every_spanning_tree_of_small_grid() {
+        // A 2x2 grid has exactly four spanning trees, one for each omitted edge.
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        let mut counts = [0; 4];
+        for _ in 0..400 {
+            let mut maze = Maze::new(2, 2);
+            Wilson.carve(&mut maze, &mut rng);
+            let omitted = [
+                (Coordinates::new(0, 0), Direction::East),
+                (Coordinates::new(0, 0), Direction::North),
+                (Coordinates::new(1, 1), Direction::South),
+                (Coordinates::new(1, 1), Direction::West),
+            ]
+            .iter()
+            .position(|(coordinates, direction)| {
+                maze.cell(coordinates).unwrap().edge(direction) == Some(CellEdge::Wall)
+            })
+            .unwrap();
+            counts[omitted] += 1;
+        }
+        for count in counts {
+            assert!((60..140).contains(&count), "Not uniform: {counts:?}");
+        }
+    }
+}
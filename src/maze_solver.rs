@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+
+use crate::maze::cell_edge::CellEdge;
+use crate::maze::coordinates::Coordinates;
+use crate::maze::direction::Direction;
+use crate::maze::Maze;
+
+/// The number of steps from a starting cell to every cell in a [Maze].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distances {
+    columns: u32,
+    rows: u32,
+    levels: u32,
+    distances: Vec<Option<u32>>,
+}
+
+/// Associated functions to use a Distances struct.
+impl Distances {
+    /// Returns the number of steps to the cell at the given coordinates, or None if it can't
+    /// be reached.
+    pub fn distance(&self, coordinates: &Coordinates) -> Option<u32> {
+        if (0..self.rows).contains(&(coordinates.row() as u32))
+            && (0..self.columns).contains(&(coordinates.column() as u32))
+            && (0..self.levels).contains(&(coordinates.level() as u32))
+        {
+            let (columns, rows) = (self.columns as i32, self.rows as i32);
+            self.distances[((coordinates.level() * rows + coordinates.row()) * columns
+                + coordinates.column()) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Returns the coordinates of the reachable cell farthest from the start, with its
+    /// distance. Ties go to the first cell in row order, from the lowest level up.
+    pub fn farthest(&self) -> (Coordinates, u32) {
+        let mut farthest = (Coordinates::new(0, 0), 0);
+        let mut found = false;
+        for (index, distance) in self.distances.iter().enumerate() {
+            if let Some(distance) = distance {
+                if !found || *distance > farthest.1 {
+                    let (columns, rows) = (self.columns as usize, self.rows as usize);
+                    let column = (index % columns) as i32;
+                    let row = (index / columns % rows) as i32;
+                    let level = (index / (columns * rows)) as i32;
+                    farthest = (Coordinates::with_level(column, row, level), *distance);
+                    found = true;
+                }
+            }
+        }
+        farthest
+    }
+}
+
+/// Returns the coordinates of the cells that can be reached in one step from the cell at the
+/// given coordinates, with the direction of each.
+///
+/// A step through a tunnel passes under the crossing cell and reaches the cell beyond it. The
+/// crossing's own tunnel edges can't be used from the crossing, which is above the tunnel.
+pub fn moves(maze: &Maze, coordinates: &Coordinates) -> Vec<(Direction, Coordinates)> {
+    match maze.cell(coordinates) {
+        Some(cell) => maze
+            .directions()
+            .iter()
+            .filter_map(|&direction| match cell.edge(&direction) {
+                Some(CellEdge::Passage) => {
+                    Some((direction, *coordinates + direction.coordinates()))
+                }
+                Some(CellEdge::Tunnel) if !cell.is_crossing() => Some((
+                    direction,
+                    *coordinates + direction.coordinates() + direction.coordinates(),
+                )),
+                _ => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Returns the number of steps from the cell at the given coordinates to every other cell.
+pub fn distances(maze: &Maze, start: &Coordinates) -> Distances {
+    let mut distances = vec![None; maze.grid_size()];
+    distances[maze.get_index(start)] = Some(0);
+    let mut queue = VecDeque::from([(*start, 0)]);
+    while let Some((coordinates, distance)) = queue.pop_front() {
+        for (_direction, neighbor_coordinates) in moves(maze, &coordinates) {
+            let index = maze.get_index(&neighbor_coordinates);
+            if distances[index].is_none() {
+                distances[index] = Some(distance + 1);
+                queue.push_back((neighbor_coordinates, distance + 1));
+            }
+        }
+    }
+    Distances {
+        columns: maze.columns(),
+        rows: maze.rows(),
+        levels: maze.levels(),
+        distances,
+    }
+}
+
+/// Returns the shortest path between the cells at the given coordinates, including both
+/// ends, or None if there is no path.
+pub fn solve(maze: &Maze, start: &Coordinates, end: &Coordinates) -> Option<Vec<Coordinates>> {
+    // Searching back from the end means following decreasing distances leads forwards.
+    let distances = distances(maze, end);
+    let mut distance = distances.distance(start)?;
+    let mut path = vec![*start];
+    let mut coordinates = *start;
+    while distance > 0 {
+        let (_direction, next) = moves(maze, &coordinates)
+            .into_iter()
+            .find(|(_direction, next)| distances.distance(next) == Some(distance - 1))?;
+        path.push(next);
+        coordinates = next;
+        distance -= 1;
+    }
+    Some(path)
+}
+
+/// Returns the shortest path from the maze's entrance to its exit, or None if it is missing
+/// either of them or there is no path. See [Maze::set_entrance] and [Maze::set_exit].
+pub fn solve_openings(maze: &Maze) -> Option<Vec<Coordinates>> {
+    let (start, _) = maze.entrance()?;
+    let (end, _) = maze.exit()?;
+    solve(maze, &start, &end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a 3x2 maze shaped like a U: up the west side, along the north row and down
+    /// the east side.
+    fn u_maze() -> Maze {
+        let mut maze = Maze::new(3, 2);
+        maze.set_edge(
+            &Coordinates::new(0, 0),
+            &Direction::North,
+            CellEdge::Passage,
+        );
+        maze.set_edge(&Coordinates::new(0, 1), &Direction::East, CellEdge::Passage);
+        maze.set_edge(&Coordinates::new(1, 1), &Direction::East, CellEdge::Passage);
+        maze.set_edge(
+            &Coordinates::new(2, 1),
+            &Direction::South,
+            CellEdge::Passage,
+        );
+        maze.set_edge(
+            &Coordinates::new(1, 1),
+            &Direction::South,
+            CellEdge::Passage,
+        );
+        maze.close_unassigned_edges();
+        maze
+    }
+
+    /// Returns a 3x3 maze with a crossing in the middle: a passage runs over it from south to
+    /// north, and a tunnel runs under it from west to east.
+    fn crossing_maze() -> Maze {
+        let mut maze = Maze::new(3, 3);
+        let middle = Coordinates::new(1, 1);
+        maze.set_edge(&middle, &Direction::North, CellEdge::Passage);
+        maze.set_edge(&middle, &Direction::South, CellEdge::Passage);
+        maze.set_edge(&middle, &Direction::West, CellEdge::Tunnel);
+        maze.set_edge(&middle, &Direction::East, CellEdge::Tunnel);
+        let mut cell = maze.cell(&middle).unwrap();
+        cell.set_crossing(true);
+        maze.set_cell(cell);
+        maze.close_unassigned_edges();
+        maze
+    }
+
+    #[test]
+    fn moves_pass_under_crossing() {
+        let maze = crossing_maze();
+        assert_eq!(
+            moves(&maze, &Coordinates::new(0, 1)),
+            vec![(Direction::East, Coordinates::new(2, 1))]
+        );
+        assert_eq!(
+            moves(&maze, &Coordinates::new(1, 1)),
+            vec![
+                (Direction::North, Coordinates::new(1, 2)),
+                (Direction::South, Coordinates::new(1, 0)),
+            ]
+        );
+        assert_eq!(
+            solve(&maze, &Coordinates::new(2, 1), &Coordinates::new(0, 1)),
+            Some(vec![Coordinates::new(2, 1), Coordinates::new(0, 1)])
+        );
+    }
+
+    #[test]
+    fn distances_follow_passages() {
+        let maze = u_maze();
+        let distances = distances(&maze, &Coordinates::new(0, 0));
+        assert_eq!(distances.distance(&Coordinates::new(0, 0)), Some(0));
+        assert_eq!(distances.distance(&Coordinates::new(1, 0)), Some(3));
+        assert_eq!(distances.distance(&Coordinates::new(2, 0)), Some(4));
+        assert_eq!(distances.distance(&Coordinates::new(5, 0)), None);
+    }
+
+    #[test]
+    fn distances_follow_stairs() {
+        let mut maze = Maze::with_levels(2, 1, 2);
+        maze.set_edge(
+            &Coordinates::new(0, 0),
+            &Direction::Above,
+            CellEdge::Passage,
+        );
+        maze.set_edge(
+            &Coordinates::with_level(0, 0, 1),
+            &Direction::East,
+            CellEdge::Passage,
+        );
+        maze.close_unassigned_edges();
+        let distances = distances(&maze, &Coordinates::new(0, 0));
+        assert_eq!(distances.distance(&Coordinates::new(1, 0)), None);
+        assert_eq!(distances.farthest(), (Coordinates::with_level(1, 0, 1), 2));
+    }
+
+    #[test]
+    fn farthest() {
+        let maze = u_maze();
+        let distances = distances(&maze, &Coordinates::new(1, 0));
+        assert_eq!(distances.farthest(), (Coordinates::new(0, 0), 3));
+    }
+
+    #[test]
+    fn solve_finds_path() {
+        let maze = u_maze();
+        let path = solve(&maze, &Coordinates::new(0, 0), &Coordinates::new(2, 0)).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                Coordinates::new(0, 0),
+                Coordinates::new(0, 1),
+                Coordinates::new(1, 1),
+                Coordinates::new(2, 1),
+                Coordinates::new(2, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn solve_to_self() {
+        let maze = u_maze();
+        let coordinates = Coordinates::new(1, 1);
+        assert_eq!(
+            solve(&maze, &coordinates, &coordinates),
+            Some(vec![coordinates])
+        );
+    }
+
+    #[test]
+    fn solve_unreachable() {
+        let mut maze = Maze::new(2, 1);
+        maze.close_unassigned_edges();
+        assert_eq!(
+            solve(&maze, &Coordinates::new(0, 0), &Coordinates::new(1, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn solve_openings_runs_from_entrance_to_exit() {
+        // The entrance is on the north row, so it comes after the exit in row order.
+        let mut maze = u_maze();
+        maze.set_entrance(&Coordinates::new(1, 1), &Direction::North);
+        maze.set_exit(&Coordinates::new(0, 0), &Direction::West);
+        let path = solve_openings(&maze).unwrap();
+        assert_eq!(path.first(), Some(&Coordinates::new(1, 1)));
+        assert_eq!(path.last(), Some(&Coordinates::new(0, 0)));
+    }
+
+    #[test]
+    fn solve_openings_without_entrance() {
+        let mut maze = u_maze();
+        maze.add_opening(&Coordinates::new(0, 0), &Direction::South);
+        maze.add_opening(&Coordinates::new(2, 0), &Direction::East);
+        assert_eq!(solve_openings(&maze), None);
+    }
+
+    #[test]
+    fn solve_openings_without_openings() {
+        assert_eq!(solve_openings(&u_maze()), None);
+    }
+}